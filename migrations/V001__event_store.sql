@@ -0,0 +1,13 @@
+-- Event store: one row per domain event, ordered per aggregate stream
+CREATE TABLE IF NOT EXISTS event_store (
+    aggregate_id UUID        NOT NULL,
+    sequence     BIGINT      NOT NULL,
+    event_type   TEXT        NOT NULL,
+    payload      JSONB       NOT NULL,
+    metadata     JSONB       NOT NULL DEFAULT '{}'::jsonb,
+    occurred_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CONSTRAINT event_store_stream_sequence_uq UNIQUE (aggregate_id, sequence),
+    CONSTRAINT event_store_sequence_positive CHECK (sequence > 0)
+);
+
+CREATE INDEX IF NOT EXISTS event_store_event_type_idx ON event_store (event_type);
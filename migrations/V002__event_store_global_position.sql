@@ -0,0 +1,24 @@
+-- Store-wide ordering for read_all/projections. Existing rows are backfilled by
+-- occurred_at, then stream and sequence; new rows take positions from the sequence.
+ALTER TABLE event_store ADD COLUMN IF NOT EXISTS position BIGINT;
+
+CREATE SEQUENCE IF NOT EXISTS event_store_position_seq OWNED BY event_store.position;
+
+UPDATE event_store AS e
+SET position = numbered.position
+FROM (
+    SELECT aggregate_id, sequence,
+           row_number() OVER (ORDER BY occurred_at, aggregate_id, sequence) AS position
+    FROM event_store
+) AS numbered
+WHERE e.aggregate_id = numbered.aggregate_id
+  AND e.sequence = numbered.sequence
+  AND e.position IS NULL;
+
+SELECT setval('event_store_position_seq', COALESCE((SELECT max(position) FROM event_store), 0) + 1, false);
+
+ALTER TABLE event_store
+    ALTER COLUMN position SET DEFAULT nextval('event_store_position_seq'),
+    ALTER COLUMN position SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS event_store_position_uq ON event_store (position);
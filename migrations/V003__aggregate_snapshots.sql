@@ -0,0 +1,7 @@
+-- Latest snapshot per aggregate, used to skip replaying long event streams
+CREATE TABLE IF NOT EXISTS aggregate_snapshots (
+    aggregate_id UUID        PRIMARY KEY,
+    sequence     BIGINT      NOT NULL,
+    state        JSONB       NOT NULL,
+    taken_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
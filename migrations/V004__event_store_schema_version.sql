@@ -0,0 +1,2 @@
+-- Payload schema version per event; rows written before versioning are treated as version 1
+ALTER TABLE event_store ADD COLUMN IF NOT EXISTS schema_version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,7 @@
+-- Deleted streams. Soft deletes keep their events; hard deletes have removed them.
+-- Either way the stream id stays reserved and appends are rejected.
+CREATE TABLE IF NOT EXISTS event_store_tombstones (
+    aggregate_id UUID        PRIMARY KEY,
+    hard_deleted BOOLEAN     NOT NULL DEFAULT FALSE,
+    deleted_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
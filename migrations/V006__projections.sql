@@ -0,0 +1,6 @@
+-- Key/value read models. "C" collation keeps key order byte-wise so prefix and range scans use the primary key index.
+CREATE TABLE IF NOT EXISTS projections (
+    key        TEXT COLLATE "C" PRIMARY KEY,
+    value      BYTEA            NOT NULL,
+    updated_at TIMESTAMPTZ      NOT NULL DEFAULT now()
+);
@@ -0,0 +1,6 @@
+-- Last event-store position applied by each projection, updated in the same transaction as its writes
+CREATE TABLE IF NOT EXISTS projection_checkpoints (
+    projection TEXT        PRIMARY KEY,
+    position   BIGINT      NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,7 @@
+-- Per-key revision for compare-and-set and optional expiry for cache-like read models.
+-- Expired rows are hidden from reads and removed by the expiry sweeper.
+ALTER TABLE projections
+    ADD COLUMN IF NOT EXISTS revision   BIGINT      NOT NULL DEFAULT 1,
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS projections_expires_at_idx ON projections (expires_at) WHERE expires_at IS NOT NULL;
@@ -0,0 +1,18 @@
+-- Transactional outbox: rows are inserted with the domain change and published to Kafka by the relay
+CREATE TABLE IF NOT EXISTS outbox (
+    id              UUID        PRIMARY KEY,
+    seq             BIGSERIAL   NOT NULL UNIQUE,
+    topic           TEXT        NOT NULL,
+    message_key     TEXT,
+    payload         JSONB       NOT NULL,
+    headers         JSONB       NOT NULL DEFAULT '[]',
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+    attempts        INTEGER     NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    locked_until    TIMESTAMPTZ,
+    last_error      TEXT,
+    sent_at         TIMESTAMPTZ,
+    failed_at       TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS outbox_pending_idx ON outbox (seq) WHERE sent_at IS NULL AND failed_at IS NULL;
@@ -0,0 +1,9 @@
+-- Messages already processed per handler, used to skip redeliveries
+CREATE TABLE IF NOT EXISTS inbox (
+    handler      TEXT        NOT NULL,
+    message_id   TEXT        NOT NULL,
+    processed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (handler, message_id)
+);
+
+CREATE INDEX IF NOT EXISTS inbox_processed_at_idx ON inbox (processed_at);
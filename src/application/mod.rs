@@ -1,4 +1,5 @@
 pub mod dtos;
 pub mod ports;
+pub mod projections;
 pub mod queries;
 pub mod services;
@@ -1,12 +1,125 @@
+use std::pin::Pin;
 use async_trait::async_trait;
+use futures::Stream;
 use uuid::Uuid;
 use serde_json::Value;
 use crate::shared::errors::InfraResult;
-use crate::shared::types::{EventEnvelope, EventMetadata};
+use crate::shared::types::{EventEnvelope, EventMetadata, EventPosition, EventVersion, ExpectedVersion};
+
+/// Never-ending stream of stored events produced by a subscription
+pub type EventStream = Pin<Box<dyn Stream<Item = InfraResult<EventEnvelope<Value>>> + Send>>;
+
+/// Order in which a stream slice is returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadDirection {
+    /// Oldest first
+    #[default]
+    Forward,
+    /// Newest first
+    Backward,
+}
+
+/// Slice of a stream to read with `EventStore::read_stream_slice`
+///
+/// Sequence bounds are inclusive; `max_count` is applied after the bounds and
+/// event-type filter, in the requested direction.
+#[derive(Debug, Clone, Default)]
+pub struct StreamReadOptions {
+    pub from_sequence: Option<EventVersion>,
+    pub to_sequence: Option<EventVersion>,
+    pub max_count: Option<usize>,
+    pub direction: ReadDirection,
+    pub event_types: Option<Vec<String>>,
+}
+
+impl StreamReadOptions {
+    /// Whole stream, oldest first
+    pub fn forward() -> Self {
+        Self::default()
+    }
+
+    /// The `count` most recent events, newest first
+    pub fn latest(count: usize) -> Self {
+        Self { max_count: Some(count), direction: ReadDirection::Backward, ..Self::default() }
+    }
+
+    pub fn from_sequence(mut self, sequence: EventVersion) -> Self {
+        self.from_sequence = Some(sequence);
+        self
+    }
+
+    pub fn to_sequence(mut self, sequence: EventVersion) -> Self {
+        self.to_sequence = Some(sequence);
+        self
+    }
+
+    pub fn max_count(mut self, count: usize) -> Self {
+        self.max_count = Some(count);
+        self
+    }
+
+    pub fn backwards(mut self) -> Self {
+        self.direction = ReadDirection::Backward;
+        self
+    }
+
+    /// Only return events whose type is one of `event_types`
+    pub fn event_types<I, S>(mut self, event_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.event_types = Some(event_types.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Whether an event falls inside the sequence bounds and type filter
+    pub fn matches(&self, event: &EventEnvelope<Value>) -> bool {
+        self.from_sequence.is_none_or(|from| event.sequence >= from)
+            && self.to_sequence.is_none_or(|to| event.sequence <= to)
+            && self.event_types.as_ref().is_none_or(|types| types.contains(&event.event_type))
+    }
+}
 
 #[async_trait]
 pub trait EventStore: Send + Sync {
-    async fn append(&self, aggregate_id: Uuid, events: Vec<(String, Value)>, metadata: EventMetadata) -> InfraResult<Vec<EventEnvelope<Value>>>;
+    /// Append events to a stream, failing with `InfrastructureError::Concurrency`
+    /// when the stream is not at `expected_version` and with
+    /// `InfrastructureError::StreamDeleted` when the stream has been deleted
+    async fn append(&self, aggregate_id: Uuid, expected_version: ExpectedVersion, events: Vec<(String, Value)>, metadata: EventMetadata) -> InfraResult<Vec<EventEnvelope<Value>>>;
     async fn read_stream(&self, aggregate_id: Uuid) -> InfraResult<Vec<EventEnvelope<Value>>>;
-}
 
+    /// Read the events of a stream with a sequence greater than `after_sequence`
+    async fn read_stream_from(&self, aggregate_id: Uuid, after_sequence: EventVersion) -> InfraResult<Vec<EventEnvelope<Value>>>;
+
+    /// Read a bounded, optionally filtered slice of a stream
+    async fn read_stream_slice(&self, aggregate_id: Uuid, options: &StreamReadOptions) -> InfraResult<Vec<EventEnvelope<Value>>>;
+
+    /// Read up to `limit` events across all streams with a position greater than
+    /// `from_position`, in commit order. Pass 0 to start from the beginning and the
+    /// last returned `position` to fetch the next page.
+    async fn read_all(&self, from_position: EventPosition, limit: usize) -> InfraResult<Vec<EventEnvelope<Value>>>;
+
+    /// Position of the most recently committed event (0 for an empty store)
+    async fn last_position(&self) -> InfraResult<EventPosition>;
+
+    /// Soft-delete a stream: its events stay readable but every further append is rejected
+    async fn tombstone_stream(&self, aggregate_id: Uuid) -> InfraResult<()>;
+
+    /// Permanently remove a stream's events (data retention). The stream stays tombstoned
+    /// so its id cannot be reused; snapshots must be removed through `SnapshotStore::delete`.
+    async fn delete_stream(&self, aggregate_id: Uuid) -> InfraResult<()>;
+
+    /// Remove events with a sequence lower than `before_sequence`, typically after a
+    /// snapshot. The latest event is always kept so the stream retains its version.
+    /// Fails with `StreamDeleted` on a tombstoned stream.
+    async fn truncate_stream_before(&self, aggregate_id: Uuid, before_sequence: EventVersion) -> InfraResult<()>;
+
+    /// Deliver every event after `from_position` across all streams, then keep
+    /// delivering new events as they are committed
+    async fn subscribe_all(&self, from_position: EventPosition) -> InfraResult<EventStream>;
+
+    /// Deliver events of one stream with a sequence greater than `from_sequence`,
+    /// then keep delivering new events appended to it
+    async fn subscribe_stream(&self, aggregate_id: Uuid, from_sequence: EventVersion) -> InfraResult<EventStream>;
+}
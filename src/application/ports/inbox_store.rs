@@ -0,0 +1,20 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::shared::errors::InfraResult;
+
+/// Records which messages each handler has already processed, for idempotent consumption
+///
+/// Entries are keyed by handler name and message id, so several handlers can each
+/// process the same message once.
+#[async_trait]
+pub trait InboxStore: Send + Sync {
+    /// Whether `handler` already processed `message_id`
+    async fn contains(&self, handler: &str, message_id: &str) -> InfraResult<bool>;
+
+    /// Mark `message_id` processed by `handler`; returns false if it already was
+    async fn record(&self, handler: &str, message_id: &str) -> InfraResult<bool>;
+
+    /// Forget entries recorded more than `older_than` ago, returning how many were removed.
+    /// Keep them for longer than messages can be redelivered.
+    async fn purge_older_than(&self, older_than: Duration) -> InfraResult<u64>;
+}
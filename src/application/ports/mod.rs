@@ -1,5 +1,11 @@
 pub mod event_store;
+pub mod inbox_store;
+pub mod outbox_store;
 pub mod projection_store;
+pub mod snapshot_store;
 
 pub use event_store::EventStore;
-pub use projection_store::ProjectionStore;
+pub use inbox_store::InboxStore;
+pub use outbox_store::{OutboxMessage, OutboxStore};
+pub use projection_store::{CheckpointStore, ProjectionEntry, ProjectionRevision, ProjectionStore, ProjectionWrite, VersionedEntry};
+pub use snapshot_store::{Snapshot, SnapshotPolicy, SnapshotStore};
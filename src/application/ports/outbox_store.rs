@@ -0,0 +1,62 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::shared::errors::InfraResult;
+
+/// Message recorded in the outbox, published later by the relay
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxMessage {
+    pub id: Uuid,
+    pub topic: String,
+    pub key: Option<String>,
+    pub payload: Value,
+    pub headers: Vec<(String, String)>,
+    pub created_at: DateTime<Utc>,
+    /// Failed publish attempts so far
+    pub attempts: u32,
+}
+
+impl OutboxMessage {
+    pub fn new(topic: impl Into<String>, payload: Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            topic: topic.into(),
+            key: None,
+            payload,
+            headers: Vec::new(),
+            created_at: Utc::now(),
+            attempts: 0,
+        }
+    }
+
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+}
+
+/// Pending side of the transactional outbox, consumed by the relay
+///
+/// Messages are written in the same transaction as the domain change (see the adapter),
+/// claimed under a lease so several relays can run, and marked sent once published.
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+    /// Lease up to `limit` due messages, oldest first; other relays skip them until the
+    /// lease expires or they are released
+    async fn claim(&self, limit: usize, lease: Duration) -> InfraResult<Vec<OutboxMessage>>;
+
+    async fn mark_sent(&self, id: Uuid) -> InfraResult<()>;
+
+    /// Record a failed attempt and retry after `retry_in`, or give up for good when `None`
+    async fn mark_failed(&self, id: Uuid, error: &str, retry_in: Option<Duration>) -> InfraResult<()>;
+
+    /// Return claimed messages without counting an attempt
+    async fn release(&self, ids: &[Uuid]) -> InfraResult<()>;
+}
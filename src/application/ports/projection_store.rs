@@ -1,15 +1,106 @@
+use std::time::Duration;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use crate::shared::errors::InfraResult;
+use crate::shared::types::EventPosition;
+
+/// Key and raw value of a stored projection
+pub type ProjectionEntry = (String, Vec<u8>);
+
+/// Per-key revision, starting at 1 and bumped by every write (a deleted or expired key starts over)
+pub type ProjectionRevision = u64;
+
+/// Stored projection together with its revision and expiry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionedEntry {
+    pub value: Vec<u8>,
+    pub revision: ProjectionRevision,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Buffered change to a projection, applied by `CheckpointStore::commit`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectionWrite {
+    Save(String, Vec<u8>),
+    Delete(String),
+}
 
 /// Generic projection store trait for read models
+///
+/// Keys are ordered byte-wise, so hierarchical keys such as `user:{id}:subscription:{id}`
+/// can be listed with `scan_prefix`. Expired entries are never returned, even before
+/// `purge_expired` has removed them.
 #[async_trait]
 pub trait ProjectionStore: Send + Sync {
-    /// Save or update a projection
+    /// Save or update a projection, clearing any expiry
     async fn save(&self, key: &str, data: &[u8]) -> InfraResult<()>;
+
+    /// Save or update a projection that expires after `ttl`
+    async fn save_with_ttl(&self, key: &str, data: &[u8], ttl: Duration) -> InfraResult<()>;
     
     /// Retrieve a projection
     async fn get(&self, key: &str) -> InfraResult<Option<Vec<u8>>>;
     
+    /// Retrieve a projection with its revision, for a later `compare_and_set`
+    async fn get_versioned(&self, key: &str) -> InfraResult<Option<VersionedEntry>>;
+
     /// Delete a projection
     async fn delete(&self, key: &str) -> InfraResult<()>;
+
+    /// Write `data` only if the key is still at revision `expected` (`None`: the key must
+    /// not exist) and return the new revision. Fails with `InfrastructureError::Concurrency`
+    /// when another writer got there first. `ttl` sets the expiry, `None` clears it.
+    async fn compare_and_set(
+        &self,
+        key: &str,
+        expected: Option<ProjectionRevision>,
+        data: &[u8],
+        ttl: Option<Duration>,
+    ) -> InfraResult<ProjectionRevision>;
+
+    /// Delete the key only if it is still at revision `expected`, failing with
+    /// `InfrastructureError::Concurrency` otherwise
+    async fn compare_and_delete(&self, key: &str, expected: ProjectionRevision) -> InfraResult<()>;
+
+    /// Remove expired entries, returning how many were removed
+    async fn purge_expired(&self) -> InfraResult<u64>;
+
+    /// Up to `limit` entries whose key starts with `prefix`, ordered by key
+    async fn scan_prefix(&self, prefix: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>>;
+
+    /// Up to `limit` entries with `start <= key < end`, ordered by key.
+    /// Pass the last returned key followed by `'\0'` as `start` to fetch the next page.
+    async fn scan_range(&self, start: &str, end: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>>;
+
+    /// Retrieve several projections; the result is aligned with `keys`
+    async fn get_many(&self, keys: &[String]) -> InfraResult<Vec<Option<Vec<u8>>>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
+    }
+
+    /// Save or update several projections
+    async fn save_many(&self, entries: Vec<ProjectionEntry>) -> InfraResult<()> {
+        for (key, data) in entries {
+            self.save(&key, &data).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks how far each projection has processed the event log
+///
+/// Implemented by projection stores that can apply projection writes and the
+/// checkpoint update in a single transaction.
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    /// Last position applied by `projection` (0 if it never ran)
+    async fn load_checkpoint(&self, projection: &str) -> InfraResult<EventPosition>;
+
+    /// Atomically apply `writes` and move the checkpoint from `from` to `to`.
+    /// Fails with `InfrastructureError::Concurrency`, applying nothing, when the stored
+    /// checkpoint is no longer `from`.
+    async fn commit(&self, projection: &str, from: EventPosition, to: EventPosition, writes: Vec<ProjectionWrite>) -> InfraResult<()>;
 }
@@ -0,0 +1,46 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::shared::errors::InfraResult;
+use crate::shared::types::EventVersion;
+
+/// Serialized aggregate state as of a given stream sequence
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub aggregate_id: Uuid,
+    pub sequence: EventVersion,
+    pub state: Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// When to take a new snapshot while saving an aggregate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotPolicy {
+    #[default]
+    Never,
+    /// Snapshot once at least N events have been appended since the last snapshot
+    EveryNEvents(u64),
+}
+
+impl SnapshotPolicy {
+    pub fn should_snapshot(&self, last_snapshot: EventVersion, current: EventVersion) -> bool {
+        match self {
+            SnapshotPolicy::Never => false,
+            SnapshotPolicy::EveryNEvents(n) => *n > 0 && current.saturating_sub(last_snapshot) >= *n,
+        }
+    }
+}
+
+/// Stores the latest snapshot per aggregate
+#[async_trait]
+pub trait SnapshotStore: Send + Sync {
+    /// Save a snapshot; an older snapshot never replaces a newer one
+    async fn save(&self, snapshot: Snapshot) -> InfraResult<()>;
+
+    /// Load the latest snapshot of an aggregate
+    async fn load(&self, aggregate_id: Uuid) -> InfraResult<Option<Snapshot>>;
+
+    /// Remove the snapshot of an aggregate
+    async fn delete(&self, aggregate_id: Uuid) -> InfraResult<()>;
+}
@@ -0,0 +1,11 @@
+pub mod namespaced;
+pub mod projection;
+pub mod rebuild;
+pub mod runner;
+pub mod versions;
+
+pub use namespaced::NamespacedProjectionStore;
+pub use projection::{Projection, ProjectionContext};
+pub use rebuild::{ProjectionRebuilder, RebuildProgress};
+pub use runner::ProjectionRunner;
+pub use versions::ProjectionVersions;
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::application::ports::projection_store::{
+    CheckpointStore, ProjectionEntry, ProjectionRevision, ProjectionStore, ProjectionWrite, VersionedEntry,
+};
+use crate::shared::errors::InfraResult;
+use crate::shared::types::EventPosition;
+
+/// Projection store decorator that confines keys and checkpoints to a namespace
+///
+/// Every key is stored as `{namespace}/{key}` and returned without the prefix, so a
+/// projection can be built side by side with another version of itself. An empty
+/// namespace passes keys through unchanged. `purge_expired` is not scoped: it purges
+/// the whole underlying store.
+pub struct NamespacedProjectionStore<S> {
+    inner: Arc<S>,
+    prefix: String,
+}
+
+impl<S> NamespacedProjectionStore<S> {
+    pub fn new(inner: Arc<S>, namespace: &str) -> Self {
+        let prefix = if namespace.is_empty() { String::new() } else { format!("{namespace}/") };
+        Self { inner, prefix }
+    }
+
+    pub fn inner(&self) -> &Arc<S> {
+        &self.inner
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    fn strip(&self, entries: Vec<ProjectionEntry>) -> Vec<ProjectionEntry> {
+        let len = self.prefix.len();
+        entries.into_iter().map(|(key, value)| (key[len..].to_string(), value)).collect()
+    }
+}
+
+#[async_trait]
+impl<S: ProjectionStore + 'static> ProjectionStore for NamespacedProjectionStore<S> {
+    async fn save(&self, key: &str, data: &[u8]) -> InfraResult<()> {
+        self.inner.save(&self.key(key), data).await
+    }
+
+    async fn save_with_ttl(&self, key: &str, data: &[u8], ttl: Duration) -> InfraResult<()> {
+        self.inner.save_with_ttl(&self.key(key), data, ttl).await
+    }
+
+    async fn get(&self, key: &str) -> InfraResult<Option<Vec<u8>>> {
+        self.inner.get(&self.key(key)).await
+    }
+
+    async fn get_versioned(&self, key: &str) -> InfraResult<Option<VersionedEntry>> {
+        self.inner.get_versioned(&self.key(key)).await
+    }
+
+    async fn delete(&self, key: &str) -> InfraResult<()> {
+        self.inner.delete(&self.key(key)).await
+    }
+
+    async fn compare_and_set(
+        &self,
+        key: &str,
+        expected: Option<ProjectionRevision>,
+        data: &[u8],
+        ttl: Option<Duration>,
+    ) -> InfraResult<ProjectionRevision> {
+        self.inner.compare_and_set(&self.key(key), expected, data, ttl).await
+    }
+
+    async fn compare_and_delete(&self, key: &str, expected: ProjectionRevision) -> InfraResult<()> {
+        self.inner.compare_and_delete(&self.key(key), expected).await
+    }
+
+    async fn purge_expired(&self) -> InfraResult<u64> {
+        self.inner.purge_expired().await
+    }
+
+    async fn scan_prefix(&self, prefix: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>> {
+        Ok(self.strip(self.inner.scan_prefix(&self.key(prefix), limit).await?))
+    }
+
+    async fn scan_range(&self, start: &str, end: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>> {
+        Ok(self.strip(self.inner.scan_range(&self.key(start), &self.key(end), limit).await?))
+    }
+
+    async fn get_many(&self, keys: &[String]) -> InfraResult<Vec<Option<Vec<u8>>>> {
+        let keys: Vec<String> = keys.iter().map(|k| self.key(k)).collect();
+        self.inner.get_many(&keys).await
+    }
+
+    async fn save_many(&self, entries: Vec<ProjectionEntry>) -> InfraResult<()> {
+        self.inner.save_many(entries.into_iter().map(|(k, v)| (self.key(&k), v)).collect()).await
+    }
+}
+
+#[async_trait]
+impl<S: CheckpointStore + 'static> CheckpointStore for NamespacedProjectionStore<S> {
+    async fn load_checkpoint(&self, projection: &str) -> InfraResult<EventPosition> {
+        self.inner.load_checkpoint(&self.key(projection)).await
+    }
+
+    async fn commit(&self, projection: &str, from: EventPosition, to: EventPosition, writes: Vec<ProjectionWrite>) -> InfraResult<()> {
+        let writes = writes
+            .into_iter()
+            .map(|write| match write {
+                ProjectionWrite::Save(key, data) => ProjectionWrite::Save(self.key(&key), data),
+                ProjectionWrite::Delete(key) => ProjectionWrite::Delete(self.key(&key)),
+            })
+            .collect();
+        self.inner.commit(&self.key(projection), from, to, writes).await
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+use async_trait::async_trait;
+use serde_json::Value;
+use crate::application::ports::projection_store::{ProjectionStore, ProjectionWrite};
+use crate::shared::errors::InfraResult;
+use crate::shared::types::EventEnvelope;
+
+/// Read model built from the global event log
+#[async_trait]
+pub trait Projection: Send + Sync {
+    /// Stable name, used as the checkpoint key
+    fn name(&self) -> &str;
+
+    /// Apply one event. Writes go through `ctx` and are committed together with the checkpoint.
+    async fn handle(&self, event: &EventEnvelope<Value>, ctx: &mut ProjectionContext<'_>) -> InfraResult<()>;
+}
+
+/// Write buffer handed to a projection for one batch of events
+///
+/// `get` sees writes buffered earlier in the same batch; scans on the underlying
+/// store do not.
+pub struct ProjectionContext<'a> {
+    store: &'a dyn ProjectionStore,
+    writes: BTreeMap<String, Option<Vec<u8>>>,
+}
+
+impl<'a> ProjectionContext<'a> {
+    pub fn new(store: &'a dyn ProjectionStore) -> Self {
+        Self { store, writes: BTreeMap::new() }
+    }
+
+    /// Store the projection reads from
+    pub fn store(&self) -> &'a dyn ProjectionStore {
+        self.store
+    }
+
+    pub async fn get(&self, key: &str) -> InfraResult<Option<Vec<u8>>> {
+        match self.writes.get(key) {
+            Some(pending) => Ok(pending.clone()),
+            None => self.store.get(key).await,
+        }
+    }
+
+    pub fn save(&mut self, key: impl Into<String>, data: Vec<u8>) {
+        self.writes.insert(key.into(), Some(data));
+    }
+
+    pub fn delete(&mut self, key: impl Into<String>) {
+        self.writes.insert(key.into(), None);
+    }
+
+    /// Buffered writes, last write per key
+    pub fn into_writes(self) -> Vec<ProjectionWrite> {
+        self.writes
+            .into_iter()
+            .map(|(key, data)| match data {
+                Some(data) => ProjectionWrite::Save(key, data),
+                None => ProjectionWrite::Delete(key),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,109 @@
+use std::{sync::Arc, time::Duration};
+use futures::StreamExt;
+use tokio::sync::watch;
+use tracing::{debug, error, info};
+use crate::application::ports::event_store::EventStore;
+use crate::application::ports::projection_store::{CheckpointStore, ProjectionStore};
+use crate::application::projections::projection::{Projection, ProjectionContext};
+use crate::shared::errors::InfraResult;
+use crate::shared::types::EventPosition;
+
+/// Drives a projection from the event store, resuming from its durable checkpoint
+///
+/// Each batch of events is committed together with the new checkpoint, so after a
+/// crash or restart processing resumes right after the last committed event and every
+/// event is applied exactly once per projection.
+pub struct ProjectionRunner<S> {
+    events: Arc<dyn EventStore>,
+    store: Arc<S>,
+    projection: Arc<dyn Projection>,
+    batch_size: usize,
+    retry_delay: Duration,
+    stop: watch::Sender<bool>,
+}
+
+impl<S> ProjectionRunner<S>
+where
+    S: ProjectionStore + CheckpointStore + 'static,
+{
+    pub fn new(events: Arc<dyn EventStore>, store: Arc<S>, projection: Arc<dyn Projection>) -> Self {
+        Self {
+            events,
+            store,
+            projection,
+            batch_size: 100,
+            retry_delay: Duration::from_secs(1),
+            stop: watch::channel(false).0,
+        }
+    }
+
+    /// Maximum number of events committed per transaction
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Delay before resuming from the checkpoint after a failed batch
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Last position committed for this projection
+    pub async fn checkpoint(&self) -> InfraResult<EventPosition> {
+        self.store.load_checkpoint(self.projection.name()).await
+    }
+
+    /// Process events until `stop` is called. A failed batch is discarded and
+    /// retried from the last checkpoint after the retry delay.
+    pub async fn run(&self) {
+        let name = self.projection.name();
+        let mut stop = self.stop.subscribe();
+        info!(projection = name, "Starting projection runner");
+
+        loop {
+            match self.run_from_checkpoint(&mut stop).await {
+                Ok(()) => break,
+                Err(e) => {
+                    error!(projection = name, error = %e, "Projection batch failed, resuming from checkpoint");
+                    tokio::select! {
+                        _ = stop.wait_for(|stopped| *stopped) => break,
+                        _ = tokio::time::sleep(self.retry_delay) => {}
+                    }
+                }
+            }
+        }
+
+        info!(projection = name, "Projection runner stopped");
+    }
+
+    pub fn stop(&self) {
+        self.stop.send_replace(true);
+    }
+
+    async fn run_from_checkpoint(&self, stop: &mut watch::Receiver<bool>) -> InfraResult<()> {
+        let name = self.projection.name();
+        let mut checkpoint = self.store.load_checkpoint(name).await?;
+        let mut batches = self.events.subscribe_all(checkpoint).await?.ready_chunks(self.batch_size);
+
+        loop {
+            let batch = tokio::select! {
+                _ = stop.wait_for(|stopped| *stopped) => return Ok(()),
+                batch = batches.next() => batch,
+            };
+            let Some(batch) = batch else { return Ok(()) };
+
+            let mut ctx = ProjectionContext::new(self.store.as_ref());
+            let mut position = checkpoint;
+            for event in batch {
+                let event = event?;
+                self.projection.handle(&event, &mut ctx).await?;
+                position = event.position;
+            }
+
+            self.store.commit(name, checkpoint, position, ctx.into_writes()).await?;
+            debug!(projection = name, from = checkpoint, to = position, "Projection checkpoint committed");
+            checkpoint = position;
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use crate::application::ports::projection_store::{CheckpointStore, ProjectionStore};
+use crate::application::projections::namespaced::NamespacedProjectionStore;
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// Reserved key prefix holding the active version of each projection
+const VERSION_KEY_PREFIX: &str = "__projection_versions/";
+
+/// Reserved key prefix marking versions whose purge has started but not finished
+const PURGE_KEY_PREFIX: &str = "__projection_purges/";
+
+/// Tracks which version of each projection readers should use
+///
+/// Version `n > 0` of projection `name` lives in namespace `{name}@v{n}`; version 0 is
+/// the un-namespaced layout used by a plain `ProjectionRunner`. The active version is a
+/// single key, so switching it is atomic for every adapter.
+pub struct ProjectionVersions<S> {
+    store: Arc<S>,
+}
+
+impl<S> Clone for ProjectionVersions<S> {
+    fn clone(&self) -> Self {
+        Self { store: self.store.clone() }
+    }
+}
+
+impl<S: ProjectionStore + 'static> ProjectionVersions<S> {
+    pub fn new(store: Arc<S>) -> Self {
+        Self { store }
+    }
+
+    /// Namespace holding `version` of projection `name`
+    pub fn namespace(name: &str, version: u32) -> String {
+        if version == 0 { String::new() } else { format!("{name}@v{version}") }
+    }
+
+    /// Version readers currently use (0 until a rebuild has been activated)
+    pub async fn active_version(&self, name: &str) -> InfraResult<u32> {
+        let Some(raw) = self.store.get(&format!("{VERSION_KEY_PREFIX}{name}")).await? else { return Ok(0) };
+        std::str::from_utf8(&raw)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| InfrastructureError::Serialization(format!("invalid active version for projection {name}")))
+    }
+
+    /// Point readers at `version`, unless a purge of it is unfinished
+    pub async fn activate(&self, name: &str, version: u32) -> InfraResult<()> {
+        self.ensure_not_purging(name, version).await?;
+        self.store.save(&format!("{VERSION_KEY_PREFIX}{name}"), version.to_string().as_bytes()).await
+    }
+
+    /// Store scoped to a specific version, for runners and rebuilds
+    pub fn store_for(&self, name: &str, version: u32) -> NamespacedProjectionStore<S> {
+        NamespacedProjectionStore::new(self.store.clone(), &Self::namespace(name, version))
+    }
+
+    /// Store scoped to the active version, for readers. Resolve it per request so a
+    /// switch is picked up immediately.
+    pub async fn reader(&self, name: &str) -> InfraResult<NamespacedProjectionStore<S>> {
+        Ok(self.store_for(name, self.active_version(name).await?))
+    }
+
+    /// Whether a purge of `version` started and has not completed; re-run it to finish
+    pub async fn purge_pending(&self, name: &str, version: u32) -> InfraResult<bool> {
+        Ok(self.store.get(&purge_key(name, version)).await?.is_some())
+    }
+
+    /// Reject work on a version that is half purged, whose leftover keys would corrupt it
+    pub(crate) async fn ensure_not_purging(&self, name: &str, version: u32) -> InfraResult<()> {
+        if self.purge_pending(name, version).await? {
+            return Err(InfrastructureError::InvalidQuery(format!(
+                "purge of version {version} of projection {name} is unfinished; re-run it first"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<S: ProjectionStore + CheckpointStore + 'static> ProjectionVersions<S> {
+    /// Delete every key of an inactive version `n > 0`, e.g. the previous one after a swap,
+    /// and reset its checkpoint so the version can be rebuilt from scratch. Version 0 has
+    /// no namespace of its own; purge it with `purge_unversioned`.
+    ///
+    /// Keys are deleted one at a time. Until every key is gone the version is marked (see
+    /// `purge_pending`) and cannot be rebuilt or activated; purging again is safe and
+    /// finishes an interrupted purge.
+    pub async fn purge(&self, name: &str, version: u32) -> InfraResult<usize> {
+        if version == 0 {
+            return Err(InfrastructureError::InvalidQuery(format!(
+                "version 0 of projection {name} is un-namespaced; use purge_unversioned"
+            )));
+        }
+        self.ensure_inactive(name, version).await?;
+        self.store.save(&purge_key(name, version), b"").await?;
+        let removed = self.delete_prefixed(&format!("{}/", Self::namespace(name, version)), |_| true).await?;
+        self.reset_checkpoint(name, version).await?;
+        self.store.delete(&purge_key(name, version)).await?;
+        Ok(removed)
+    }
+
+    /// Delete the un-namespaced (version 0) keys of projection `name` starting with
+    /// `key_prefix` and reset its version 0 checkpoint, once a rebuilt version is active.
+    /// Keys of namespaced versions and the reserved version records are never touched.
+    /// Interrupted purges are handled as in `purge`.
+    pub async fn purge_unversioned(&self, name: &str, key_prefix: &str) -> InfraResult<usize> {
+        if key_prefix.is_empty() {
+            return Err(InfrastructureError::InvalidQuery(format!(
+                "purging version 0 of projection {name} needs a non-empty key prefix"
+            )));
+        }
+        self.ensure_inactive(name, 0).await?;
+        self.store.save(&purge_key(name, 0), b"").await?;
+        let removed = self.delete_prefixed(key_prefix, |key| !is_reserved(key)).await?;
+        self.reset_checkpoint(name, 0).await?;
+        self.store.delete(&purge_key(name, 0)).await?;
+        Ok(removed)
+    }
+
+    async fn ensure_inactive(&self, name: &str, version: u32) -> InfraResult<()> {
+        if version == self.active_version(name).await? {
+            return Err(InfrastructureError::InvalidQuery(format!("refusing to purge active version {version} of projection {name}")));
+        }
+        Ok(())
+    }
+
+    async fn delete_prefixed(&self, prefix: &str, select: impl Fn(&str) -> bool) -> InfraResult<usize> {
+        // Unselected keys stay in place, so widen each scan by the number already skipped
+        let mut skipped = HashSet::new();
+        let mut removed = 0;
+        loop {
+            let limit = skipped.len() + 500;
+            let page = self.store.scan_prefix(prefix, limit).await?;
+            let full = page.len() == limit;
+            let mut progressed = false;
+            for (key, _) in page {
+                if select(&key) {
+                    self.store.delete(&key).await?;
+                    removed += 1;
+                    progressed = true;
+                } else {
+                    progressed |= skipped.insert(key);
+                }
+            }
+            if !full || !progressed {
+                break;
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn reset_checkpoint(&self, name: &str, version: u32) -> InfraResult<()> {
+        let scoped = self.store_for(name, version);
+        let checkpoint = scoped.load_checkpoint(name).await?;
+        if checkpoint != 0 {
+            scoped.commit(name, checkpoint, 0, Vec::new()).await?;
+        }
+        Ok(())
+    }
+}
+
+fn purge_key(name: &str, version: u32) -> String {
+    format!("{PURGE_KEY_PREFIX}{name}@v{version}")
+}
+
+/// Version records and keys inside a `{name}@v{n}/` namespace
+fn is_reserved(key: &str) -> bool {
+    if key.starts_with(VERSION_KEY_PREFIX) || key.starts_with(PURGE_KEY_PREFIX) {
+        return true;
+    }
+    let Some((namespace, _)) = key.split_once('/') else { return false };
+    namespace
+        .rsplit_once("@v")
+        .is_some_and(|(_, version)| !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()))
+}
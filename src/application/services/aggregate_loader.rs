@@ -0,0 +1,96 @@
+use std::sync::Arc;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use uuid::Uuid;
+use crate::application::ports::event_store::{EventStore, StreamReadOptions};
+use crate::application::ports::snapshot_store::{Snapshot, SnapshotPolicy, SnapshotStore};
+use crate::shared::errors::AppResult;
+use crate::shared::traits::AggregateRoot;
+use crate::shared::types::{EventEnvelope, EventVersion, LoadedAggregate};
+use crate::shared::utils::now;
+
+/// Rebuilds aggregates from the event store, starting from the latest snapshot when available
+#[derive(Clone)]
+pub struct AggregateLoader {
+    events: Arc<dyn EventStore>,
+    snapshots: Option<Arc<dyn SnapshotStore>>,
+    policy: SnapshotPolicy,
+}
+
+impl AggregateLoader {
+    pub fn new(events: Arc<dyn EventStore>) -> Self {
+        Self { events, snapshots: None, policy: SnapshotPolicy::Never }
+    }
+
+    pub fn with_snapshots(mut self, snapshots: Arc<dyn SnapshotStore>, policy: SnapshotPolicy) -> Self {
+        self.snapshots = Some(snapshots);
+        self.policy = policy;
+        self
+    }
+
+    pub fn event_store(&self) -> &Arc<dyn EventStore> {
+        &self.events
+    }
+
+    /// Load an aggregate, replaying only the events recorded after its latest snapshot.
+    /// Returns `None` when neither a snapshot nor any event exists.
+    ///
+    /// A snapshot that no longer decodes is ignored only while the stream still starts at
+    /// sequence 1; once events before it have been truncated the decode error is returned,
+    /// since replaying what is left would build the wrong state.
+    pub async fn load<A, F>(&self, aggregate_id: Uuid, decode: F) -> AppResult<Option<LoadedAggregate<A>>>
+    where
+        A: AggregateRoot + Default + DeserializeOwned,
+        F: Fn(&EventEnvelope<Value>) -> AppResult<A::Event>,
+    {
+        let (mut aggregate, snapshot_version) = match self.load_snapshot::<A>(aggregate_id).await? {
+            Some((aggregate, sequence)) => (aggregate, sequence),
+            None => (A::default(), 0),
+        };
+
+        let events = self.events.read_stream_from(aggregate_id, snapshot_version).await?;
+        if events.is_empty() && snapshot_version == 0 {
+            return Ok(None);
+        }
+
+        let mut version = snapshot_version;
+        for envelope in &events {
+            aggregate.apply_event(&decode(envelope)?);
+            version = envelope.sequence;
+        }
+
+        Ok(Some(LoadedAggregate { aggregate_id, aggregate, version, snapshot_version }))
+    }
+
+    /// Take a snapshot when the policy says enough events were appended since the last one.
+    /// Returns true when a snapshot was written.
+    pub async fn snapshot_if_due<A: Serialize>(&self, aggregate_id: Uuid, aggregate: &A, snapshot_version: EventVersion, version: EventVersion) -> AppResult<bool> {
+        let Some(store) = &self.snapshots else { return Ok(false) };
+        if !self.policy.should_snapshot(snapshot_version, version) {
+            return Ok(false);
+        }
+
+        let snapshot = Snapshot { aggregate_id, sequence: version, state: serde_json::to_value(aggregate)?, timestamp: now() };
+        store.save(snapshot).await?;
+        Ok(true)
+    }
+
+    async fn load_snapshot<A: DeserializeOwned>(&self, aggregate_id: Uuid) -> AppResult<Option<(A, EventVersion)>> {
+        let Some(store) = &self.snapshots else { return Ok(None) };
+        let Some(snapshot) = store.load(aggregate_id).await? else { return Ok(None) };
+
+        match serde_json::from_value(snapshot.state) {
+            Ok(aggregate) => Ok(Some((aggregate, snapshot.sequence))),
+            Err(e) => {
+                // State shape changed since the snapshot was taken; replay from the start if we still can
+                let first = self.events.read_stream_slice(aggregate_id, &StreamReadOptions::forward().max_count(1)).await?;
+                if first.first().map(|event| event.sequence) != Some(1) {
+                    return Err(e.into());
+                }
+                warn!(%aggregate_id, error = %e, "Ignoring unreadable snapshot");
+                Ok(None)
+            }
+        }
+    }
+}
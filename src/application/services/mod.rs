@@ -1,2 +1,4 @@
 // Application services (sagas, orchestrations) placeholder
+pub mod aggregate_loader;
 
+pub use aggregate_loader::AggregateLoader;
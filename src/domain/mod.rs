@@ -2,3 +2,4 @@
 // Expose submodules so other layers (application/infrastructure) can import them.
 // Domain core cleaned: user-related modules removed.
 // Add new domain modules here when needed.
+pub mod repositories;
@@ -1,10 +1,17 @@
 use async_trait::async_trait;
 use uuid::Uuid;
-use crate::domain::aggregates::user_aggregate::UserAggregate;
 use crate::shared::errors::DomainResult;
+use crate::shared::traits::AggregateRoot;
+use crate::shared::types::{EventMetadata, LoadedAggregate};
 
+/// Persistence contract for event-sourced aggregates
 #[async_trait]
-pub trait UserAggregateRepository: Send + Sync {
-    async fn load(&self, id: Uuid) -> DomainResult<Option<UserAggregate>>;
-    async fn save(&self, aggregate: &mut UserAggregate) -> DomainResult<()>;
+pub trait AggregateRepository<A: AggregateRoot>: Send + Sync {
+    /// Load an aggregate, or `None` when it has never been saved
+    async fn load(&self, id: Uuid) -> DomainResult<Option<LoadedAggregate<A>>>;
+
+    /// Persist the aggregate's uncommitted events. Fails with `DomainError::Concurrency`
+    /// when the stream moved past the version the aggregate was loaded at and with
+    /// `DomainError::StreamDeleted` when the stream has been deleted.
+    async fn save(&self, aggregate: &mut LoadedAggregate<A>, metadata: EventMetadata) -> DomainResult<()>;
 }
@@ -69,8 +69,19 @@ pub struct KafkaConsumerSettings {
     pub auto_offset_reset: Option<String>,
     pub enable_auto_commit: Option<bool>,
     pub auto_commit_interval_ms: Option<u64>,
+    /// Store offsets only after the handler succeeds. Stored offsets are then committed
+    /// every `auto_commit_interval_ms`, so this implies `enable_auto_commit = true`.
+    pub commit_after_handle: Option<bool>,
+    pub max_redeliveries: Option<u32>,
     pub session_timeout_ms: Option<u64>,
+    pub max_poll_interval_ms: Option<u64>,
     pub max_poll_records: Option<usize>,
+    pub max_retries: Option<u32>,
+    pub retry_backoff_ms: Option<u64>,
+    pub max_retry_backoff_ms: Option<u64>,
+    #[serde(default)]
+    pub retry_topics: Vec<crate::infrastructure::messaging::kafka::RetryTopic>,
+    pub dead_letter_topic: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -89,6 +100,20 @@ pub struct DatabaseSettings {
     pub connect_retry_delay_ms: Option<u64>,
 }
 
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventStoreSettings {
+    pub snapshot_every: Option<u64>,
+}
+
+impl EventStoreSettings {
+    pub fn snapshot_policy(&self) -> crate::application::ports::SnapshotPolicy {
+        match self.snapshot_every {
+            Some(n) if n > 0 => crate::application::ports::SnapshotPolicy::EveryNEvents(n),
+            _ => crate::application::ports::SnapshotPolicy::Never,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Settings {
     pub app: AppSettings,
@@ -102,6 +127,8 @@ pub struct Settings {
     pub kafka_consumers: HashMap<String, KafkaConsumerSettings>,
     pub market: MarketSettings,
     pub database: DatabaseSettings,
+    #[serde(default)]
+    pub event_store: EventStoreSettings,
 }
 
 impl Default for Settings {
@@ -148,7 +175,8 @@ impl Default for Settings {
             database: DatabaseSettings {
                 max_connect_attempts: Some(5),
                 connect_retry_delay_ms: Some(2000),
-            }
+            },
+            event_store: EventStoreSettings::default(),
         }
     }
 }
@@ -186,6 +214,9 @@ impl Settings {
         if let Some(v) = pick(&["DATABASE_MAX_CONNECT_ATTEMPTS"]) { if let Ok(n) = v.parse() { settings.database.max_connect_attempts = Some(n); } }
         if let Some(v) = pick(&["DATABASE_CONNECT_RETRY_DELAY_MS"]) { if let Ok(n) = v.parse() { settings.database.connect_retry_delay_ms = Some(n); } }
 
+        // Event store settings
+        if let Some(v) = pick(&["EVENT_STORE_SNAPSHOT_EVERY"]) { if let Ok(n) = v.parse() { settings.event_store.snapshot_every = Some(n); } }
+
         settings
     }
 
@@ -233,8 +264,16 @@ impl Settings {
             auto_offset_reset: c.auto_offset_reset.clone(),
             enable_auto_commit: c.enable_auto_commit,
             auto_commit_interval_ms: c.auto_commit_interval_ms,
+            commit_after_handle: c.commit_after_handle,
+            max_redeliveries: c.max_redeliveries,
             session_timeout_ms: c.session_timeout_ms,
+            max_poll_interval_ms: c.max_poll_interval_ms,
             max_poll_records: c.max_poll_records,
+            max_retries: c.max_retries,
+            retry_backoff_ms: c.retry_backoff_ms,
+            max_retry_backoff_ms: c.max_retry_backoff_ms,
+            retry_topics: c.retry_topics.clone(),
+            dead_letter_topic: c.dead_letter_topic.clone(),
         })
     }
 }
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use crate::shared::errors::{AppResult, InfrastructureError};
+
+type Encoder<E> = Box<dyn Fn(&E) -> Option<AppResult<Value>> + Send + Sync>;
+type Decoder<E> = Box<dyn Fn(Value) -> AppResult<E> + Send + Sync>;
+
+/// Maps an aggregate's event type to and from the `(event_type, Value)` pairs kept in the event store
+///
+/// Each payload type is registered under a stable name together with the functions that
+/// wrap it into, and extract it from, the aggregate's event enum:
+///
+/// ```ignore
+/// let registry = EventRegistry::new()
+///     .register("AccountOpened", AccountEvent::Opened, |e| match e { AccountEvent::Opened(p) => Some(p), _ => None })
+///     .register("AccountClosed", AccountEvent::Closed, |e| match e { AccountEvent::Closed(p) => Some(p), _ => None });
+/// ```
+pub struct EventRegistry<E> {
+    encoders: Vec<(String, Encoder<E>)>,
+    decoders: HashMap<String, Decoder<E>>,
+}
+
+impl<E> Default for EventRegistry<E> {
+    fn default() -> Self {
+        Self { encoders: Vec::new(), decoders: HashMap::new() }
+    }
+}
+
+impl<E: 'static> EventRegistry<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register payload type `P` under `event_type`
+    pub fn register<P>(mut self, event_type: &str, wrap: fn(P) -> E, unwrap: fn(&E) -> Option<&P>) -> Self
+    where
+        P: Serialize + DeserializeOwned + 'static,
+    {
+        self.encoders.push((
+            event_type.to_string(),
+            Box::new(move |event| unwrap(event).map(|payload| serde_json::to_value(payload).map_err(Into::into))),
+        ));
+        self.decoders.insert(
+            event_type.to_string(),
+            Box::new(move |payload| Ok(wrap(serde_json::from_value(payload)?))),
+        );
+        self
+    }
+
+    /// Convert an event into its stored name and payload
+    pub fn encode(&self, event: &E) -> AppResult<(String, Value)> {
+        for (event_type, encoder) in &self.encoders {
+            if let Some(payload) = encoder(event) {
+                return Ok((event_type.clone(), payload?));
+            }
+        }
+        Err(InfrastructureError::Serialization(format!("unregistered event variant of {}", std::any::type_name::<E>())).into())
+    }
+
+    /// Rebuild an event from its stored name and payload
+    pub fn decode(&self, event_type: &str, payload: Value) -> AppResult<E> {
+        let decoder = self
+            .decoders
+            .get(event_type)
+            .ok_or_else(|| InfrastructureError::Serialization(format!("unknown event type: {event_type}")))?;
+        decoder(payload)
+    }
+}
@@ -1,36 +1,194 @@
-use std::collections::HashMap;
-use tokio::sync::RwLock;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{watch, RwLock};
 use uuid::Uuid;
 use serde_json::Value;
 use chrono::Utc;
 use async_trait::async_trait;
-use crate::application::ports::event_store::EventStore;
-use crate::shared::errors::InfraResult;
-use crate::shared::types::{EventEnvelope, EventMetadata};
-use once_cell::sync::Lazy;
+use crate::application::ports::event_store::{EventStore, EventStream, ReadDirection, StreamReadOptions};
+use crate::infrastructure::event_store::subscription;
+use crate::infrastructure::event_store::upcasting::UpcasterRegistry;
+use crate::shared::errors::{InfraResult, InfrastructureError};
+use crate::shared::types::{EventEnvelope, EventMetadata, EventPosition, EventVersion, ExpectedVersion};
 
-static STORE: Lazy<RwLock<HashMap<Uuid, Vec<EventEnvelope<Value>>>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+#[derive(Default)]
+struct State {
+    streams: HashMap<Uuid, Vec<EventEnvelope<Value>>>,
+    log: BTreeMap<EventPosition, EventEnvelope<Value>>,
+    tombstones: HashSet<Uuid>,
+    last_position: EventPosition,
+}
+
+impl State {
+    fn remove_stream(&mut self, aggregate_id: &Uuid) {
+        if let Some(events) = self.streams.remove(aggregate_id) {
+            for event in events {
+                self.log.remove(&event.position);
+            }
+        }
+    }
+}
+
+/// In-memory event store for tests and local runs
+///
+/// Every `new()` owns its own storage; clones share it.
+#[derive(Clone)]
+pub struct InMemoryEventStore {
+    state: Arc<RwLock<State>>,
+    head: Arc<watch::Sender<EventPosition>>,
+    upcasters: Arc<UpcasterRegistry>,
+}
+
+impl Default for InMemoryEventStore {
+    fn default() -> Self {
+        Self { state: Arc::default(), head: Arc::new(watch::channel(0).0), upcasters: Arc::default() }
+    }
+}
 
-#[derive(Clone, Default)]
-pub struct InMemoryEventStore;
-impl InMemoryEventStore { pub fn new() -> Self { Self } }
+impl InMemoryEventStore {
+    pub fn new() -> Self { Self::default() }
+
+    /// Upgrade older payloads on read and stamp appends with the current schema versions
+    pub fn with_upcasters(mut self, upcasters: UpcasterRegistry) -> Self {
+        self.upcasters = Arc::new(upcasters);
+        self
+    }
+
+    /// Ids of all streams that currently hold events
+    pub async fn stream_ids(&self) -> Vec<Uuid> {
+        self.state.read().await.streams.keys().copied().collect()
+    }
+
+    /// Total number of stored events across all streams
+    pub async fn event_count(&self) -> usize {
+        self.state.read().await.log.len()
+    }
+
+    /// Remove every stream and reset the global position
+    pub async fn clear(&self) {
+        *self.state.write().await = State::default();
+        self.head.send_replace(0);
+    }
+
+    /// Ids of all tombstoned (soft- or hard-deleted) streams
+    pub async fn tombstoned_ids(&self) -> Vec<Uuid> {
+        self.state.read().await.tombstones.iter().copied().collect()
+    }
+
+    /// Replace a stream with pre-built envelopes, bypassing version checks and tombstones.
+    /// Seeded events are given fresh global positions in the order supplied and keep
+    /// their `schema_version`, so older payload shapes can be seeded to exercise upcasters.
+    pub async fn seed(&self, aggregate_id: Uuid, events: Vec<EventEnvelope<Value>>) {
+        let mut guard = self.state.write().await;
+        guard.remove_stream(&aggregate_id);
+        guard.tombstones.remove(&aggregate_id);
+        let mut stream = Vec::with_capacity(events.len());
+        for mut event in events {
+            guard.last_position += 1;
+            event.position = guard.last_position;
+            guard.log.insert(event.position, event.clone());
+            stream.push(event);
+        }
+        guard.streams.insert(aggregate_id, stream);
+        self.head.send_replace(guard.last_position);
+    }
+}
 
 #[async_trait]
 impl EventStore for InMemoryEventStore {
-    async fn append(&self, aggregate_id: Uuid, events: Vec<(String, Value)>, metadata: EventMetadata) -> InfraResult<Vec<EventEnvelope<Value>>> {
-        let mut guard = STORE.write().await;
-        let stream = guard.entry(aggregate_id).or_default();
+    async fn append(&self, aggregate_id: Uuid, expected_version: ExpectedVersion, events: Vec<(String, Value)>, metadata: EventMetadata) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let mut guard = self.state.write().await;
+        let state = &mut *guard;
+        if state.tombstones.contains(&aggregate_id) {
+            return Err(InfrastructureError::StreamDeleted(format!("stream {aggregate_id} is deleted and accepts no new events")));
+        }
+        let current = state.streams.get(&aggregate_id).and_then(|s| s.last()).map(|e| e.sequence).unwrap_or(0);
+        if !expected_version.matches(current) {
+            return Err(InfrastructureError::Concurrency(format!(
+                "stream {aggregate_id} is at version {current}, expected {expected_version:?}"
+            )));
+        }
+        let stream = state.streams.entry(aggregate_id).or_default();
         let mut created = Vec::with_capacity(events.len());
-        for (ty, payload) in events.into_iter() {
-            let seq = (stream.len() as u64) + 1;
-            let envelope = EventEnvelope { aggregate_id, sequence: seq, event_type: ty, payload, metadata: metadata.clone(), timestamp: Utc::now() };
+        for (offset, (ty, payload)) in events.into_iter().enumerate() {
+            let seq = current + offset as u64 + 1;
+            state.last_position += 1;
+            let schema_version = self.upcasters.current_version(&ty);
+            let envelope = EventEnvelope { aggregate_id, sequence: seq, position: state.last_position, event_type: ty, schema_version, payload, metadata: metadata.clone(), timestamp: Utc::now() };
             stream.push(envelope.clone());
+            state.log.insert(envelope.position, envelope.clone());
             created.push(envelope);
         }
+        if !created.is_empty() {
+            self.head.send_replace(state.last_position);
+        }
         Ok(created)
     }
     async fn read_stream(&self, aggregate_id: Uuid) -> InfraResult<Vec<EventEnvelope<Value>>> {
-        let guard = STORE.read().await;
-        Ok(guard.get(&aggregate_id).cloned().unwrap_or_default())
+        let events = self.state.read().await.streams.get(&aggregate_id).cloned().unwrap_or_default();
+        self.upcasters.upcast_all(events)
+    }
+    async fn read_stream_from(&self, aggregate_id: Uuid, after_sequence: EventVersion) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let events = {
+            let guard = self.state.read().await;
+            guard.streams.get(&aggregate_id).map(|s| s.iter().filter(|e| e.sequence > after_sequence).cloned().collect()).unwrap_or_default()
+        };
+        self.upcasters.upcast_all(events)
+    }
+    async fn read_stream_slice(&self, aggregate_id: Uuid, options: &StreamReadOptions) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let events = {
+            let guard = self.state.read().await;
+            let Some(stream) = guard.streams.get(&aggregate_id) else { return Ok(Vec::new()) };
+            let matching = stream.iter().filter(|e| options.matches(e));
+            let limit = options.max_count.unwrap_or(usize::MAX);
+            match options.direction {
+                ReadDirection::Forward => matching.take(limit).cloned().collect(),
+                ReadDirection::Backward => matching.rev().take(limit).cloned().collect(),
+            }
+        };
+        self.upcasters.upcast_all(events)
+    }
+    async fn read_all(&self, from_position: EventPosition, limit: usize) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let events = {
+            let guard = self.state.read().await;
+            guard.log.range(from_position.saturating_add(1)..).take(limit).map(|(_, e)| e.clone()).collect()
+        };
+        self.upcasters.upcast_all(events)
+    }
+    async fn last_position(&self) -> InfraResult<EventPosition> {
+        Ok(self.state.read().await.last_position)
+    }
+    async fn tombstone_stream(&self, aggregate_id: Uuid) -> InfraResult<()> {
+        self.state.write().await.tombstones.insert(aggregate_id);
+        Ok(())
+    }
+    async fn delete_stream(&self, aggregate_id: Uuid) -> InfraResult<()> {
+        let mut guard = self.state.write().await;
+        guard.remove_stream(&aggregate_id);
+        guard.tombstones.insert(aggregate_id);
+        Ok(())
+    }
+    async fn truncate_stream_before(&self, aggregate_id: Uuid, before_sequence: EventVersion) -> InfraResult<()> {
+        let mut guard = self.state.write().await;
+        let state = &mut *guard;
+        if state.tombstones.contains(&aggregate_id) {
+            return Err(InfrastructureError::StreamDeleted(format!("stream {aggregate_id} is deleted and cannot be truncated")));
+        }
+        let Some(stream) = state.streams.get_mut(&aggregate_id) else { return Ok(()) };
+        let cutoff = before_sequence.min(stream.last().map(|e| e.sequence).unwrap_or(0));
+        stream.retain(|event| {
+            let keep = event.sequence >= cutoff;
+            if !keep {
+                state.log.remove(&event.position);
+            }
+            keep
+        });
+        Ok(())
+    }
+    async fn subscribe_all(&self, from_position: EventPosition) -> InfraResult<EventStream> {
+        Ok(subscription::subscribe_all(self.clone(), self.head.subscribe(), from_position))
+    }
+    async fn subscribe_stream(&self, aggregate_id: Uuid, from_sequence: EventVersion) -> InfraResult<EventStream> {
+        Ok(subscription::subscribe_stream(self.clone(), self.head.subscribe(), aggregate_id, from_sequence))
     }
 }
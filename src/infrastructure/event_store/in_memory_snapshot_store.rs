@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use async_trait::async_trait;
+use crate::application::ports::snapshot_store::{Snapshot, SnapshotStore};
+use crate::shared::errors::InfraResult;
+
+/// In-memory snapshot store for tests and local runs
+#[derive(Clone, Default)]
+pub struct InMemorySnapshotStore {
+    snapshots: Arc<RwLock<HashMap<Uuid, Snapshot>>>,
+}
+
+impl InMemorySnapshotStore {
+    pub fn new() -> Self { Self::default() }
+
+    /// Remove every snapshot
+    pub async fn clear(&self) {
+        self.snapshots.write().await.clear();
+    }
+}
+
+#[async_trait]
+impl SnapshotStore for InMemorySnapshotStore {
+    async fn save(&self, snapshot: Snapshot) -> InfraResult<()> {
+        let mut guard = self.snapshots.write().await;
+        let newer = guard.get(&snapshot.aggregate_id).map(|s| snapshot.sequence > s.sequence).unwrap_or(true);
+        if newer {
+            guard.insert(snapshot.aggregate_id, snapshot);
+        }
+        Ok(())
+    }
+    async fn load(&self, aggregate_id: Uuid) -> InfraResult<Option<Snapshot>> {
+        Ok(self.snapshots.read().await.get(&aggregate_id).cloned())
+    }
+    async fn delete(&self, aggregate_id: Uuid) -> InfraResult<()> {
+        self.snapshots.write().await.remove(&aggregate_id);
+        Ok(())
+    }
+}
@@ -1,2 +1,14 @@
+pub mod event_registry;
 pub mod in_memory_event_store;
+pub mod in_memory_snapshot_store;
+pub mod postgres_event_store;
+pub mod postgres_snapshot_store;
+mod subscription;
+pub mod upcasting;
 
+pub use event_registry::EventRegistry;
+pub use in_memory_event_store::InMemoryEventStore;
+pub use in_memory_snapshot_store::InMemorySnapshotStore;
+pub use postgres_event_store::PostgresEventStore;
+pub use postgres_snapshot_store::PostgresSnapshotStore;
+pub use upcasting::UpcasterRegistry;
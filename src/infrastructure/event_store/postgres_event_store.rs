@@ -0,0 +1,347 @@
+use std::{sync::Arc, time::Duration};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{postgres::{PgListener, PgRow}, PgPool, Postgres, QueryBuilder, Row, Transaction};
+use tokio::sync::{watch, OnceCell};
+use tracing::warn;
+use uuid::Uuid;
+use crate::application::ports::event_store::{EventStore, EventStream, ReadDirection, StreamReadOptions};
+use crate::infrastructure::event_store::subscription;
+use crate::infrastructure::event_store::upcasting::UpcasterRegistry;
+use crate::shared::errors::{InfraResult, InfrastructureError};
+use crate::shared::types::{EventEnvelope, EventMetadata, EventPosition, EventVersion, ExpectedVersion};
+
+/// Advisory lock key serializing writes across all streams
+///
+/// Positions come from a sequence, so without the lock a transaction could commit
+/// position N+1 while N is still in flight, and a `read_all` reader would move past N
+/// for good. Holding the lock until commit hands out positions in commit order at the
+/// cost of one writing transaction at a time store-wide: throughput is capped at roughly
+/// one append per commit latency however many streams are written. Measure with
+/// `tests/postgres_append_throughput.rs`; batch events into fewer appends if it binds.
+const APPEND_LOCK_KEY: i64 = 0x6576_656e_7473; // "events"
+
+/// NOTIFY channel carrying the last committed position of each append
+const NOTIFY_CHANNEL: &str = "event_store";
+
+const SELECT_COLUMNS: &str = "aggregate_id, sequence, position, event_type, schema_version, payload, metadata, occurred_at";
+
+/// PostgreSQL-backed event store
+///
+/// Events live in the `event_store` table (see `migrations/V001__event_store.sql`).
+/// The unique `(aggregate_id, sequence)` constraint is the final guard against
+/// concurrent writers: a lost race surfaces as `InfrastructureError::Concurrency`.
+///
+/// Appends, truncations and deletions take a transaction-scoped advisory lock so
+/// `position` values are handed out in commit order and `read_all` never observes gaps
+/// that fill in later; this serializes writes across streams (see `APPEND_LOCK_KEY`).
+/// Subscriptions are woken through `LISTEN/NOTIFY`; the listener starts on first use.
+#[derive(Clone)]
+pub struct PostgresEventStore {
+    pool: PgPool,
+    head: Arc<OnceCell<watch::Receiver<EventPosition>>>,
+    upcasters: Arc<UpcasterRegistry>,
+}
+
+impl PostgresEventStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool, head: Arc::new(OnceCell::new()), upcasters: Arc::default() }
+    }
+
+    /// Upgrade older payloads on read and stamp appends with the current schema versions
+    pub fn with_upcasters(mut self, upcasters: UpcasterRegistry) -> Self {
+        self.upcasters = Arc::new(upcasters);
+        self
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Receiver tracking the latest committed position, starting the listener if needed
+    async fn head(&self) -> InfraResult<watch::Receiver<EventPosition>> {
+        self.head
+            .get_or_try_init(|| async {
+                let mut listener = PgListener::connect_with(&self.pool).await.map_err(db_error)?;
+                listener.listen(NOTIFY_CHANNEL).await.map_err(db_error)?;
+
+                let current: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(position), 0) FROM event_store")
+                    .fetch_one(&self.pool)
+                    .await
+                    .map_err(db_error)?;
+                let (tx, rx) = watch::channel(current as u64);
+
+                tokio::spawn(async move {
+                    while !tx.is_closed() {
+                        match listener.recv().await {
+                            Ok(notification) => {
+                                if let Ok(position) = notification.payload().parse::<u64>() {
+                                    tx.send_if_modified(|head| {
+                                        let advanced = position > *head;
+                                        if advanced { *head = position; }
+                                        advanced
+                                    });
+                                }
+                            }
+                            Err(e) => {
+                                warn!(error = %e, "Event store listener error, retrying");
+                                tokio::time::sleep(Duration::from_secs(1)).await;
+                            }
+                        }
+                    }
+                });
+
+                Ok(rx)
+            })
+            .await
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl EventStore for PostgresEventStore {
+    async fn append(&self, aggregate_id: Uuid, expected_version: ExpectedVersion, events: Vec<(String, Value)>, metadata: EventMetadata) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let metadata_json = serde_json::to_value(&metadata)
+            .map_err(|e| InfrastructureError::Serialization(format!("event metadata: {}", e)))?;
+
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+
+        lock_appends(&mut tx).await?;
+        ensure_not_deleted(&mut tx, aggregate_id).await?;
+
+        let current: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(sequence), 0) FROM event_store WHERE aggregate_id = $1")
+            .bind(aggregate_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        let current = current as u64;
+
+        if !expected_version.matches(current) {
+            return Err(InfrastructureError::Concurrency(format!(
+                "stream {aggregate_id} is at version {current}, expected {expected_version:?}"
+            )));
+        }
+
+        let mut created = Vec::with_capacity(events.len());
+        for (offset, (event_type, payload)) in events.into_iter().enumerate() {
+            let sequence = current + offset as u64 + 1;
+            let schema_version = self.upcasters.current_version(&event_type);
+            let (position, timestamp): (i64, DateTime<Utc>) = sqlx::query_as(
+                "INSERT INTO event_store (aggregate_id, sequence, event_type, schema_version, payload, metadata) \
+                 VALUES ($1, $2, $3, $4, $5, $6) RETURNING position, occurred_at",
+            )
+            .bind(aggregate_id)
+            .bind(sequence as i64)
+            .bind(&event_type)
+            .bind(schema_version as i32)
+            .bind(&payload)
+            .bind(&metadata_json)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| append_error(aggregate_id, e))?;
+
+            created.push(EventEnvelope { aggregate_id, sequence, position: position as u64, event_type, schema_version, payload, metadata: metadata.clone(), timestamp });
+        }
+
+        if let Some(last) = created.last() {
+            sqlx::query("SELECT pg_notify($1, $2)")
+                .bind(NOTIFY_CHANNEL)
+                .bind(last.position.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+        }
+
+        tx.commit().await.map_err(|e| append_error(aggregate_id, e))?;
+        Ok(created)
+    }
+
+    async fn read_stream(&self, aggregate_id: Uuid) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SELECT_COLUMNS} FROM event_store WHERE aggregate_id = $1 ORDER BY sequence"
+        ))
+        .bind(aggregate_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let events = rows.iter().map(envelope_from_row).collect::<InfraResult<Vec<_>>>()?;
+        self.upcasters.upcast_all(events)
+    }
+
+    async fn read_stream_from(&self, aggregate_id: Uuid, after_sequence: EventVersion) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SELECT_COLUMNS} FROM event_store WHERE aggregate_id = $1 AND sequence > $2 ORDER BY sequence"
+        ))
+        .bind(aggregate_id)
+        .bind(after_sequence as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let events = rows.iter().map(envelope_from_row).collect::<InfraResult<Vec<_>>>()?;
+        self.upcasters.upcast_all(events)
+    }
+
+    async fn read_stream_slice(&self, aggregate_id: Uuid, options: &StreamReadOptions) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let mut query = QueryBuilder::<Postgres>::new(format!("SELECT {SELECT_COLUMNS} FROM event_store WHERE aggregate_id = "));
+        query.push_bind(aggregate_id);
+        if let Some(from) = options.from_sequence {
+            query.push(" AND sequence >= ").push_bind(from as i64);
+        }
+        if let Some(to) = options.to_sequence {
+            query.push(" AND sequence <= ").push_bind(to as i64);
+        }
+        if let Some(types) = &options.event_types {
+            query.push(" AND event_type = ANY(").push_bind(types.clone()).push(")");
+        }
+        query.push(match options.direction {
+            ReadDirection::Forward => " ORDER BY sequence ASC",
+            ReadDirection::Backward => " ORDER BY sequence DESC",
+        });
+        if let Some(count) = options.max_count {
+            query.push(" LIMIT ").push_bind(count.min(i64::MAX as usize) as i64);
+        }
+
+        let rows = query.build().fetch_all(&self.pool).await.map_err(db_error)?;
+        let events = rows.iter().map(envelope_from_row).collect::<InfraResult<Vec<_>>>()?;
+        self.upcasters.upcast_all(events)
+    }
+
+    async fn read_all(&self, from_position: EventPosition, limit: usize) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {SELECT_COLUMNS} FROM event_store WHERE position > $1 ORDER BY position LIMIT $2"
+        ))
+        .bind(from_position as i64)
+        .bind(limit.min(i64::MAX as usize) as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let events = rows.iter().map(envelope_from_row).collect::<InfraResult<Vec<_>>>()?;
+        self.upcasters.upcast_all(events)
+    }
+
+    async fn last_position(&self) -> InfraResult<EventPosition> {
+        let position: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(position), 0) FROM event_store")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(position as u64)
+    }
+
+    async fn tombstone_stream(&self, aggregate_id: Uuid) -> InfraResult<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        lock_appends(&mut tx).await?;
+        insert_tombstone(&mut tx, aggregate_id, false).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn delete_stream(&self, aggregate_id: Uuid) -> InfraResult<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        lock_appends(&mut tx).await?;
+        insert_tombstone(&mut tx, aggregate_id, true).await?;
+        sqlx::query("DELETE FROM event_store WHERE aggregate_id = $1")
+            .bind(aggregate_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn truncate_stream_before(&self, aggregate_id: Uuid, before_sequence: EventVersion) -> InfraResult<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        lock_appends(&mut tx).await?;
+        ensure_not_deleted(&mut tx, aggregate_id).await?;
+        sqlx::query(
+            "DELETE FROM event_store WHERE aggregate_id = $1 AND sequence < LEAST($2, \
+             (SELECT MAX(sequence) FROM event_store WHERE aggregate_id = $1))",
+        )
+        .bind(aggregate_id)
+        .bind(before_sequence.min(i64::MAX as u64) as i64)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn subscribe_all(&self, from_position: EventPosition) -> InfraResult<EventStream> {
+        let head = self.head().await?;
+        Ok(subscription::subscribe_all(self.clone(), head, from_position))
+    }
+
+    async fn subscribe_stream(&self, aggregate_id: Uuid, from_sequence: EventVersion) -> InfraResult<EventStream> {
+        let head = self.head().await?;
+        Ok(subscription::subscribe_stream(self.clone(), head, aggregate_id, from_sequence))
+    }
+}
+
+/// Serialize with concurrent writes for the rest of the transaction
+async fn lock_appends(tx: &mut Transaction<'_, Postgres>) -> InfraResult<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(APPEND_LOCK_KEY)
+        .execute(&mut **tx)
+        .await
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Reject writes to a tombstoned stream; call with the append lock held
+async fn ensure_not_deleted(tx: &mut Transaction<'_, Postgres>, aggregate_id: Uuid) -> InfraResult<()> {
+    let deleted: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM event_store_tombstones WHERE aggregate_id = $1)")
+        .bind(aggregate_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(db_error)?;
+    if deleted {
+        return Err(InfrastructureError::StreamDeleted(format!("stream {aggregate_id} is deleted and accepts no new events")));
+    }
+    Ok(())
+}
+
+async fn insert_tombstone(tx: &mut Transaction<'_, Postgres>, aggregate_id: Uuid, hard: bool) -> InfraResult<()> {
+    sqlx::query(
+        "INSERT INTO event_store_tombstones (aggregate_id, hard_deleted) VALUES ($1, $2) \
+         ON CONFLICT (aggregate_id) DO UPDATE SET hard_deleted = event_store_tombstones.hard_deleted OR EXCLUDED.hard_deleted",
+    )
+    .bind(aggregate_id)
+    .bind(hard)
+    .execute(&mut **tx)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+fn envelope_from_row(row: &PgRow) -> InfraResult<EventEnvelope<Value>> {
+    let metadata: Value = row.try_get("metadata").map_err(db_error)?;
+    let metadata: EventMetadata = serde_json::from_value(metadata)
+        .map_err(|e| InfrastructureError::Serialization(format!("event metadata: {}", e)))?;
+    let sequence: i64 = row.try_get("sequence").map_err(db_error)?;
+    let position: i64 = row.try_get("position").map_err(db_error)?;
+    let schema_version: i32 = row.try_get("schema_version").map_err(db_error)?;
+
+    Ok(EventEnvelope {
+        aggregate_id: row.try_get("aggregate_id").map_err(db_error)?,
+        sequence: sequence as u64,
+        position: position as u64,
+        event_type: row.try_get("event_type").map_err(db_error)?,
+        schema_version: schema_version as u32,
+        payload: row.try_get("payload").map_err(db_error)?,
+        metadata,
+        timestamp: row.try_get("occurred_at").map_err(db_error)?,
+    })
+}
+
+fn append_error(aggregate_id: Uuid, e: sqlx::Error) -> InfrastructureError {
+    let unique_violation = e.as_database_error().map(|d| d.is_unique_violation()).unwrap_or(false);
+    if unique_violation {
+        InfrastructureError::Concurrency(format!("stream {aggregate_id} was modified concurrently"))
+    } else {
+        db_error(e)
+    }
+}
+
+fn db_error(e: sqlx::Error) -> InfrastructureError {
+    InfrastructureError::EventStore(e.to_string())
+}
@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use crate::application::ports::snapshot_store::{Snapshot, SnapshotStore};
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// PostgreSQL-backed snapshot store (see `migrations/V003__aggregate_snapshots.sql`)
+#[derive(Clone)]
+pub struct PostgresSnapshotStore {
+    pool: PgPool,
+}
+
+impl PostgresSnapshotStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SnapshotStore for PostgresSnapshotStore {
+    async fn save(&self, snapshot: Snapshot) -> InfraResult<()> {
+        sqlx::query(
+            "INSERT INTO aggregate_snapshots (aggregate_id, sequence, state, taken_at) VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (aggregate_id) DO UPDATE \
+             SET sequence = EXCLUDED.sequence, state = EXCLUDED.state, taken_at = EXCLUDED.taken_at \
+             WHERE aggregate_snapshots.sequence < EXCLUDED.sequence",
+        )
+        .bind(snapshot.aggregate_id)
+        .bind(snapshot.sequence as i64)
+        .bind(&snapshot.state)
+        .bind(snapshot.timestamp)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| InfrastructureError::EventStore(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn load(&self, aggregate_id: Uuid) -> InfraResult<Option<Snapshot>> {
+        let row: Option<(i64, Value, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT sequence, state, taken_at FROM aggregate_snapshots WHERE aggregate_id = $1",
+        )
+        .bind(aggregate_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| InfrastructureError::EventStore(e.to_string()))?;
+
+        Ok(row.map(|(sequence, state, timestamp)| Snapshot { aggregate_id, sequence: sequence as u64, state, timestamp }))
+    }
+
+    async fn delete(&self, aggregate_id: Uuid) -> InfraResult<()> {
+        sqlx::query("DELETE FROM aggregate_snapshots WHERE aggregate_id = $1")
+            .bind(aggregate_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| InfrastructureError::EventStore(e.to_string()))?;
+        Ok(())
+    }
+}
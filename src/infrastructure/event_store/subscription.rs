@@ -0,0 +1,85 @@
+use std::time::Duration;
+use async_stream::try_stream;
+use tokio::sync::watch;
+use uuid::Uuid;
+use crate::application::ports::event_store::{EventStore, EventStream, StreamReadOptions};
+use crate::shared::types::{EventPosition, EventVersion};
+
+/// Events fetched per `read_all` or `read_stream_slice` call while catching up
+const PAGE_SIZE: usize = 256;
+
+/// Longest a caught-up subscription waits before re-reading the log, so a missed
+/// head notification delays delivery instead of stalling it
+const IDLE_POLL: Duration = Duration::from_secs(1);
+
+/// Catch-up-then-live subscription over the global log.
+///
+/// `head` carries the latest committed position; stores bump it after every append.
+pub(crate) fn subscribe_all<S>(store: S, mut head: watch::Receiver<EventPosition>, from_position: EventPosition) -> EventStream
+where
+    S: EventStore + 'static,
+{
+    Box::pin(try_stream! {
+        let mut last_position = from_position;
+        loop {
+            let page = store.read_all(last_position, PAGE_SIZE).await?;
+            if page.is_empty() {
+                wait_for_head(&mut head).await;
+                continue;
+            }
+            for event in page {
+                last_position = event.position;
+                yield event;
+            }
+        }
+    })
+}
+
+/// Catch-up-then-live subscription over a single stream.
+///
+/// Catch-up reads the stream in `PAGE_SIZE` slices after the last delivered sequence;
+/// the live phase follows the global log from the head observed before catch-up
+/// started and drops anything already delivered.
+pub(crate) fn subscribe_stream<S>(store: S, mut head: watch::Receiver<EventPosition>, aggregate_id: Uuid, from_sequence: EventVersion) -> EventStream
+where
+    S: EventStore + 'static,
+{
+    Box::pin(try_stream! {
+        let mut last_position = *head.borrow_and_update();
+        let mut last_sequence = from_sequence;
+        loop {
+            let options = StreamReadOptions::forward().from_sequence(last_sequence + 1).max_count(PAGE_SIZE);
+            let page = store.read_stream_slice(aggregate_id, &options).await?;
+            let caught_up = page.len() < PAGE_SIZE;
+            for event in page {
+                last_sequence = event.sequence;
+                yield event;
+            }
+            if caught_up {
+                break;
+            }
+        }
+        loop {
+            let page = store.read_all(last_position, PAGE_SIZE).await?;
+            if page.is_empty() {
+                wait_for_head(&mut head).await;
+                continue;
+            }
+            for event in page {
+                last_position = event.position;
+                if event.aggregate_id == aggregate_id && event.sequence > last_sequence {
+                    last_sequence = event.sequence;
+                    yield event;
+                }
+            }
+        }
+    })
+}
+
+async fn wait_for_head(head: &mut watch::Receiver<EventPosition>) {
+    match tokio::time::timeout(IDLE_POLL, head.changed()).await {
+        Ok(Ok(())) | Err(_) => {}
+        // Store dropped its sender; fall back to plain polling
+        Ok(Err(_)) => tokio::time::sleep(IDLE_POLL).await,
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde_json::Value;
+use crate::shared::errors::InfraResult;
+use crate::shared::types::{EventEnvelope, SchemaVersion};
+
+type UpcastFn = Arc<dyn Fn(Value) -> InfraResult<Value> + Send + Sync>;
+
+/// Upcasters keyed by event type and the schema version they upgrade from
+///
+/// An upcaster registered for `(event_type, n)` turns a version `n` payload into
+/// version `n + 1`. Reads run the chain until no further upcaster applies, so old
+/// events always reach the caller in the current shape. Appends are stamped with the
+/// current version, i.e. one past the last upcaster in the chain.
+#[derive(Clone, Default)]
+pub struct UpcasterRegistry {
+    upcasters: HashMap<(String, SchemaVersion), UpcastFn>,
+}
+
+impl UpcasterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an upcaster from `from_version` to `from_version + 1` of `event_type`
+    pub fn register<F>(mut self, event_type: &str, from_version: SchemaVersion, upcast: F) -> Self
+    where
+        F: Fn(Value) -> InfraResult<Value> + Send + Sync + 'static,
+    {
+        self.upcasters.insert((event_type.to_string(), from_version), Arc::new(upcast));
+        self
+    }
+
+    /// Schema version newly appended events of `event_type` are written with
+    pub fn current_version(&self, event_type: &str) -> SchemaVersion {
+        let mut version = 1;
+        while self.upcasters.contains_key(&(event_type.to_string(), version)) {
+            version += 1;
+        }
+        version
+    }
+
+    /// Bring a stored event up to the current schema version of its type
+    pub fn upcast(&self, mut envelope: EventEnvelope<Value>) -> InfraResult<EventEnvelope<Value>> {
+        while let Some(upcast) = self.upcasters.get(&(envelope.event_type.clone(), envelope.schema_version)) {
+            envelope.payload = upcast(envelope.payload)?;
+            envelope.schema_version += 1;
+        }
+        Ok(envelope)
+    }
+
+    pub fn upcast_all(&self, envelopes: Vec<EventEnvelope<Value>>) -> InfraResult<Vec<EventEnvelope<Value>>> {
+        if self.upcasters.is_empty() {
+            return Ok(envelopes);
+        }
+        envelopes.into_iter().map(|e| self.upcast(e)).collect()
+    }
+}
@@ -0,0 +1,101 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use async_trait::async_trait;
+use tracing::debug;
+use crate::application::ports::inbox_store::InboxStore;
+use crate::infrastructure::inbox::postgres_inbox_store::PostgresInboxStore;
+use crate::infrastructure::messaging::kafka::common::ConsumedMessage;
+use crate::infrastructure::messaging::kafka::consumers::MessageHandler;
+use crate::infrastructure::repositories::UnitOfWork;
+use crate::shared::errors::InfraResult;
+
+/// Message handler whose writes go through the unit of work it is given
+#[async_trait]
+pub trait TransactionalMessageHandler<T>: Send + Sync {
+    async fn handle(&self, message: T, uow: &UnitOfWork) -> InfraResult<()>;
+
+    /// Handle a message together with its metadata; the default passes only the value to `handle`
+    async fn handle_message(&self, message: ConsumedMessage<T>, uow: &UnitOfWork) -> InfraResult<()>
+    where
+        T: Send + 'static,
+    {
+        self.handle(message.value, uow).await
+    }
+}
+
+/// Skips messages the inner handler already processed, recording completion in an inbox
+///
+/// Completion is recorded after the handler succeeds, so a crash in between processes the
+/// message again; use `TransactionalIdempotentHandler` when that must not happen.
+/// Bare values handled through `handle` have no message id and are passed straight through.
+pub struct IdempotentHandler<T, H> {
+    name: String,
+    inbox: Arc<dyn InboxStore>,
+    inner: H,
+    _phantom: PhantomData<fn(T)>,
+}
+
+impl<T, H: MessageHandler<T>> IdempotentHandler<T, H> {
+    /// `name` scopes the inbox entries, so keep it stable across deployments
+    pub fn new(name: impl Into<String>, inbox: Arc<dyn InboxStore>, inner: H) -> Self {
+        Self { name: name.into(), inbox, inner, _phantom: PhantomData }
+    }
+}
+
+#[async_trait]
+impl<T: Send + 'static, H: MessageHandler<T>> MessageHandler<T> for IdempotentHandler<T, H> {
+    async fn handle(&self, message: T) -> InfraResult<()> {
+        self.inner.handle(message).await
+    }
+
+    async fn handle_message(&self, message: ConsumedMessage<T>) -> InfraResult<()> {
+        let message_id = message.message_id();
+        if self.inbox.contains(&self.name, &message_id).await? {
+            debug!(handler = %self.name, message_id, "Skipping already processed message");
+            return Ok(());
+        }
+        self.inner.handle_message(message).await?;
+        self.inbox.record(&self.name, &message_id).await?;
+        Ok(())
+    }
+}
+
+/// Runs the inner handler and the inbox insert in one `UnitOfWork`
+///
+/// The message counts as processed exactly when the handler's writes commit; a handler
+/// error rolls both back so the message can be retried. A concurrent delivery of the
+/// same message waits on the inbox row and is then skipped.
+pub struct TransactionalIdempotentHandler<T, H> {
+    name: String,
+    inbox: PostgresInboxStore,
+    inner: H,
+    _phantom: PhantomData<fn(T)>,
+}
+
+impl<T, H: TransactionalMessageHandler<T>> TransactionalIdempotentHandler<T, H> {
+    /// `name` scopes the inbox entries, so keep it stable across deployments
+    pub fn new(name: impl Into<String>, inbox: PostgresInboxStore, inner: H) -> Self {
+        Self { name: name.into(), inbox, inner, _phantom: PhantomData }
+    }
+}
+
+#[async_trait]
+impl<T: Send + 'static, H: TransactionalMessageHandler<T>> MessageHandler<T> for TransactionalIdempotentHandler<T, H> {
+    async fn handle(&self, message: T) -> InfraResult<()> {
+        let inner = &self.inner;
+        UnitOfWork::run(self.inbox.pool(), |uow| async move { inner.handle(message, &uow).await }).await
+    }
+
+    async fn handle_message(&self, message: ConsumedMessage<T>) -> InfraResult<()> {
+        let (name, inbox, inner) = (&self.name, &self.inbox, &self.inner);
+        UnitOfWork::run(inbox.pool(), |uow| async move {
+            let message_id = message.message_id();
+            if !inbox.record_in(&uow, name, &message_id).await? {
+                debug!(handler = %name, message_id, "Skipping already processed message");
+                return Ok(());
+            }
+            inner.handle_message(message, &uow).await
+        })
+        .await
+    }
+}
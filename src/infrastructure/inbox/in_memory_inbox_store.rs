@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+use crate::application::ports::inbox_store::InboxStore;
+use crate::shared::errors::InfraResult;
+
+/// Handler name and message id
+type InboxKey = (String, String);
+
+/// In-memory inbox for tests and local runs; clones share storage
+#[derive(Clone, Default)]
+pub struct InMemoryInboxStore {
+    processed: Arc<RwLock<HashMap<InboxKey, DateTime<Utc>>>>,
+}
+
+impl InMemoryInboxStore {
+    pub fn new() -> Self { Self::default() }
+
+    pub async fn len(&self) -> usize {
+        self.processed.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.processed.read().await.is_empty()
+    }
+
+    pub async fn clear(&self) {
+        self.processed.write().await.clear();
+    }
+}
+
+#[async_trait]
+impl InboxStore for InMemoryInboxStore {
+    async fn contains(&self, handler: &str, message_id: &str) -> InfraResult<bool> {
+        Ok(self.processed.read().await.contains_key(&(handler.to_string(), message_id.to_string())))
+    }
+
+    async fn record(&self, handler: &str, message_id: &str) -> InfraResult<bool> {
+        let mut guard = self.processed.write().await;
+        let key = (handler.to_string(), message_id.to_string());
+        if guard.contains_key(&key) {
+            return Ok(false);
+        }
+        guard.insert(key, Utc::now());
+        Ok(true)
+    }
+
+    async fn purge_older_than(&self, older_than: Duration) -> InfraResult<u64> {
+        let cutoff = chrono::Duration::from_std(older_than)
+            .ok()
+            .and_then(|age| Utc::now().checked_sub_signed(age))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let mut guard = self.processed.write().await;
+        let before = guard.len();
+        guard.retain(|_, processed_at| *processed_at >= cutoff);
+        Ok((before - guard.len()) as u64)
+    }
+}
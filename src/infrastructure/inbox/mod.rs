@@ -0,0 +1,7 @@
+pub mod idempotent_handler;
+pub mod in_memory_inbox_store;
+pub mod postgres_inbox_store;
+
+pub use idempotent_handler::{IdempotentHandler, TransactionalIdempotentHandler, TransactionalMessageHandler};
+pub use in_memory_inbox_store::InMemoryInboxStore;
+pub use postgres_inbox_store::PostgresInboxStore;
@@ -0,0 +1,72 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use sqlx::{PgConnection, PgPool};
+use crate::application::ports::inbox_store::InboxStore;
+use crate::infrastructure::repositories::UnitOfWork;
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// PostgreSQL inbox (see `migrations/V010__inbox.sql`)
+///
+/// `record_in` inserts through a `UnitOfWork`, so the message counts as processed only
+/// if the handler's writes in the same unit of work commit.
+#[derive(Clone)]
+pub struct PostgresInboxStore {
+    pool: PgPool,
+}
+
+impl PostgresInboxStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Mark `message_id` processed in `uow`'s transaction; returns false if it already was.
+    /// A concurrent unit of work recording the same message waits until this one finishes.
+    pub async fn record_in(&self, uow: &UnitOfWork, handler: &str, message_id: &str) -> InfraResult<bool> {
+        let mut conn = uow.connection().await?;
+        insert(&mut conn, handler, message_id).await
+    }
+}
+
+async fn insert(conn: &mut PgConnection, handler: &str, message_id: &str) -> InfraResult<bool> {
+    let result = sqlx::query("INSERT INTO inbox (handler, message_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+        .bind(handler)
+        .bind(message_id)
+        .execute(conn)
+        .await
+        .map_err(db_error)?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[async_trait]
+impl InboxStore for PostgresInboxStore {
+    async fn contains(&self, handler: &str, message_id: &str) -> InfraResult<bool> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM inbox WHERE handler = $1 AND message_id = $2)")
+            .bind(handler)
+            .bind(message_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn record(&self, handler: &str, message_id: &str) -> InfraResult<bool> {
+        let mut conn = self.pool.acquire().await.map_err(db_error)?;
+        insert(&mut conn, handler, message_id).await
+    }
+
+    async fn purge_older_than(&self, older_than: Duration) -> InfraResult<u64> {
+        let result = sqlx::query("DELETE FROM inbox WHERE processed_at < now() - make_interval(secs => $1)")
+            .bind(older_than.as_secs_f64())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected())
+    }
+}
+
+fn db_error(e: sqlx::Error) -> InfrastructureError {
+    InfrastructureError::Database(e.to_string())
+}
@@ -1,6 +1,15 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use crate::shared::errors::{InfraResult, InfrastructureError};
 
+/// Header carrying a stable, unique message id (set by the outbox relay)
+pub const MESSAGE_ID_HEADER: &str = "message-id";
+
+/// Position of a message as first consumed, kept when it is forwarded to retry topics
+pub const ORIGINAL_TOPIC_HEADER: &str = "original-topic";
+pub const ORIGINAL_PARTITION_HEADER: &str = "original-partition";
+pub const ORIGINAL_OFFSET_HEADER: &str = "original-offset";
+
 /// Supported serialization formats for Kafka messages
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SerializationFormat {
@@ -37,6 +46,55 @@ impl<T> KafkaMessage<T> {
     }
 }
 
+/// Consumed Kafka message with its delivery metadata
+#[derive(Debug, Clone)]
+pub struct ConsumedMessage<T> {
+    pub key: Option<String>,
+    pub value: T,
+    pub headers: Vec<(String, String)>,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl<T> ConsumedMessage<T> {
+    /// Value of the first header named `key`
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// Id used to deduplicate a delivery: the `message-id` header if present, else
+    /// `topic/partition/offset` where it was first consumed (before any retry topic)
+    pub fn message_id(&self) -> String {
+        if let Some(id) = self.header(MESSAGE_ID_HEADER) {
+            return id.to_string();
+        }
+        let original = (
+            self.header(ORIGINAL_TOPIC_HEADER),
+            self.header(ORIGINAL_PARTITION_HEADER),
+            self.header(ORIGINAL_OFFSET_HEADER),
+        );
+        match original {
+            (Some(topic), Some(partition), Some(offset)) => format!("{}/{}/{}", topic, partition, offset),
+            _ => format!("{}/{}/{}", self.topic, self.partition, self.offset),
+        }
+    }
+
+    /// Swap the value, keeping the metadata
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ConsumedMessage<U> {
+        ConsumedMessage {
+            key: self.key,
+            value: f(self.value),
+            headers: self.headers,
+            topic: self.topic,
+            partition: self.partition,
+            offset: self.offset,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
 /// Trait for serializing messages to bytes
 pub trait MessageSerializer<T>: Send + Sync {
     fn serialize(&self, message: &T) -> InfraResult<Vec<u8>>;
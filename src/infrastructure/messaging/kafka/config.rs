@@ -45,8 +45,38 @@ pub struct KafkaConsumerConfig {
     pub auto_offset_reset: Option<String>,
     pub enable_auto_commit: Option<bool>,
     pub auto_commit_interval_ms: Option<u64>,
+    /// Store a message's offset only once its handler succeeded (at-least-once delivery).
+    /// Stored offsets are committed every `auto_commit_interval_ms`, so this implies
+    /// `enable_auto_commit = true`.
+    pub commit_after_handle: Option<bool>,
+    /// With `commit_after_handle`, how often a message that still fails (and could not be
+    /// forwarded) is redelivered before its offset is stored anyway and it is skipped
+    pub max_redeliveries: Option<u32>,
     pub session_timeout_ms: Option<u64>,
+    /// Longest gap between polls before the consumer is evicted from its group
+    pub max_poll_interval_ms: Option<u64>,
     pub max_poll_records: Option<usize>,
+    /// In-place retries of a failed handler before the message is forwarded
+    pub max_retries: Option<u32>,
+    pub retry_backoff_ms: Option<u64>,
+    pub max_retry_backoff_ms: Option<u64>,
+    /// Topics a still-failing message passes through, in order, before the dead-letter topic
+    #[serde(default)]
+    pub retry_topics: Vec<RetryTopic>,
+    pub dead_letter_topic: Option<String>,
+}
+
+/// Retry topic whose messages are handled again once `delay_ms` has passed
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RetryTopic {
+    pub topic: String,
+    pub delay_ms: u64,
+}
+
+impl RetryTopic {
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms)
+    }
 }
 
 impl KafkaConsumerConfig {
@@ -62,8 +92,32 @@ impl KafkaConsumerConfig {
         self.auto_commit_interval_ms.unwrap_or(5000)
     }
 
+    pub fn commit_after_handle(&self) -> bool {
+        self.commit_after_handle.unwrap_or(false)
+    }
+
+    pub fn max_redeliveries(&self) -> u32 {
+        self.max_redeliveries.unwrap_or(10)
+    }
+
     pub fn session_timeout_ms(&self) -> u64 {
         self.session_timeout_ms.unwrap_or(10000)
     }
+
+    pub fn max_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.max_poll_interval_ms.unwrap_or(300000))
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(0)
+    }
+
+    pub fn retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.retry_backoff_ms.unwrap_or(500))
+    }
+
+    pub fn max_retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.max_retry_backoff_ms.unwrap_or(30000))
+    }
 }
 
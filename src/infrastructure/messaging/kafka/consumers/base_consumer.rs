@@ -1,19 +1,38 @@
-use std::{sync::Arc, time::Duration, marker::PhantomData};
+use std::{collections::HashMap, sync::Arc, time::Duration, marker::PhantomData};
+use std::sync::Mutex as SyncMutex;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rdkafka::{
-    consumer::{Consumer, StreamConsumer},
-    ClientConfig, Message,
+    consumer::{CommitMode, Consumer, ConsumerContext, Rebalance, StreamConsumer},
+    error::KafkaError,
+    message::{BorrowedMessage, Headers},
+    types::RDKafkaErrorCode,
+    ClientConfig, ClientContext, Message, Offset, TopicPartitionList,
 };
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 use crate::infrastructure::messaging::kafka::KafkaConsumerConfig;
-use crate::infrastructure::messaging::kafka::common::MessageDeserializer;
+use crate::infrastructure::messaging::kafka::common::{ConsumedMessage, MessageDeserializer};
+use crate::infrastructure::messaging::kafka::consumers::retry_policy::{self, RetryForwarder, RetryPolicy};
 use crate::shared::errors::{InfraResult, InfrastructureError};
 
+/// Wait before redelivering a message that exhausted its retries
+const REDELIVERY_DELAY: chrono::Duration = chrono::Duration::seconds(1);
+
 /// Generic message handler trait
 #[async_trait]
 pub trait MessageHandler<T>: Send + Sync {
     async fn handle(&self, message: T) -> InfraResult<()>;
+
+    /// Handle a message together with its key, headers and position; the default
+    /// passes only the value to `handle`
+    async fn handle_message(&self, message: ConsumedMessage<T>) -> InfraResult<()>
+    where
+        T: Send + 'static,
+    {
+        self.handle(message.value).await
+    }
 }
 
 /// Generic Kafka consumer port trait
@@ -25,15 +44,35 @@ pub trait KafkaConsumerPort: Send + Sync {
 }
 
 /// Generic Kafka consumer implementation
+///
+/// With `commit_after_handle`, a message's offset is stored only after its handler
+/// succeeds and stored offsets are committed every `auto_commit_interval_ms`, on
+/// rebalance and on stop. A message that still fails after its `RetryPolicy` and could not
+/// be forwarded is redelivered (its partition paused for a second each time) up to
+/// `max_redeliveries` times and then skipped, so configure a dead-letter topic to keep
+/// such messages; messages that cannot be deserialized and have no dead-letter topic are
+/// skipped.
+///
+/// Retry topics are subscribed alongside `topics`. A retry-topic partition whose next
+/// message is not due yet, or whose message is waiting for an in-place retry, is paused
+/// until it is due, so other partitions keep flowing. With retry topics configured,
+/// offsets are stored only once a message is done, so a parked message is not committed
+/// past.
 pub struct KafkaConsumer<T, D, H>
 where
     D: MessageDeserializer<T>,
     H: MessageHandler<T>,
 {
-    consumer: Arc<StreamConsumer>,
+    consumer: Arc<StreamConsumer<RebalanceContext>>,
     deserializer: Arc<D>,
     handler: Arc<H>,
-    running: Arc<Mutex<bool>>,
+    retry: Arc<RetryPolicy>,
+    forwarder: Option<Arc<RetryForwarder>>,
+    commit_after_handle: bool,
+    manual_offset_store: bool,
+    max_redeliveries: u32,
+    stop: watch::Sender<bool>,
+    task: Mutex<Option<JoinHandle<()>>>,
     _phantom: PhantomData<T>,
 }
 
@@ -55,30 +94,53 @@ where
             "Bootstrapping Kafka consumer"
         );
 
+        // librdkafka commits stored offsets periodically, on partition revocation and on close
+        let commit_after_handle = config.commit_after_handle();
+        let enable_auto_commit = commit_after_handle || config.enable_auto_commit();
+        if commit_after_handle && config.enable_auto_commit == Some(false) {
+            warn!("commit_after_handle commits stored offsets periodically; ignoring enable_auto_commit = false");
+        }
+
         let mut client_config = ClientConfig::new();
         client_config
             .set("bootstrap.servers", &config.brokers)
             .set("group.id", &config.group_id)
             .set("auto.offset.reset", config.auto_offset_reset())
-            .set("enable.auto.commit", config.enable_auto_commit().to_string())
-            .set("session.timeout.ms", config.session_timeout_ms().to_string());
+            .set("enable.auto.commit", enable_auto_commit.to_string())
+            .set("session.timeout.ms", config.session_timeout_ms().to_string())
+            .set("max.poll.interval.ms", config.max_poll_interval().as_millis().to_string());
 
         if let Some(client_id) = &config.client_id {
             client_config.set("client.id", client_id);
         }
 
-        if config.enable_auto_commit() {
+        let retry = RetryPolicy::from_config(&config);
+
+        // Offsets of messages parked for a later retry must not be stored yet
+        let manual_offset_store = commit_after_handle || !retry.retry_topics.is_empty();
+        if manual_offset_store {
+            client_config.set("enable.auto.offset.store", "false");
+        }
+
+        if enable_auto_commit {
             client_config.set(
                 "auto.commit.interval.ms",
                 config.auto_commit_interval_ms().to_string(),
             );
         }
 
-        let consumer: StreamConsumer = client_config
-            .create()
+        let consumer: StreamConsumer<RebalanceContext> = client_config
+            .create_with_context(RebalanceContext::default())
             .map_err(|e| InfrastructureError::Kafka(format!("Failed to create consumer: {}", e)))?;
 
-        let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+        let forwarder = if retry.forwards() { Some(Arc::new(RetryForwarder::new(&config)?)) } else { None };
+
+        let mut topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+        for retry_topic in &retry.retry_topics {
+            if !topics.contains(&retry_topic.topic.as_str()) {
+                topics.push(&retry_topic.topic);
+            }
+        }
         consumer
             .subscribe(&topics)
             .map_err(|e| InfrastructureError::Kafka(format!("Failed to subscribe: {}", e)))?;
@@ -89,7 +151,13 @@ where
             consumer: Arc::new(consumer),
             deserializer: Arc::new(deserializer),
             handler: Arc::new(handler),
-            running: Arc::new(Mutex::new(false)),
+            retry: Arc::new(retry),
+            forwarder,
+            commit_after_handle,
+            manual_offset_store,
+            max_redeliveries: config.max_redeliveries(),
+            stop: watch::channel(false).0,
+            task: Mutex::new(None),
             _phantom: PhantomData,
         }))
     }
@@ -103,44 +171,100 @@ where
     H: MessageHandler<T> + Send + Sync + 'static,
 {
     async fn start(&self) -> InfraResult<()> {
-        let mut running = self.running.lock().await;
-        if *running {
+        let mut task = self.task.lock().await;
+        if task.as_ref().is_some_and(|task| !task.is_finished()) {
             warn!("Consumer already running");
             return Ok(());
         }
-        *running = true;
-        drop(running);
 
         info!("Starting Kafka consumer");
 
         let consumer = self.consumer.clone();
         let deserializer = self.deserializer.clone();
         let handler = self.handler.clone();
-        let running = self.running.clone();
+        let retry = self.retry.clone();
+        let forwarder = self.forwarder.clone();
+        let commit_after_handle = self.commit_after_handle;
+        let manual_offset_store = self.manual_offset_store;
+        let max_redeliveries = self.max_redeliveries;
+        self.stop.send_replace(false);
+        let mut stopped = self.stop.subscribe();
 
-        tokio::spawn(async move {
+        *task = Some(tokio::spawn(async move {
+            // Failed attempts at the message each partition is stuck on
+            let mut deliveries: HashMap<(String, i32), Delivery> = HashMap::new();
+            // Partitions paused until their next message is due
+            let mut paused: HashMap<(String, i32), DateTime<Utc>> = HashMap::new();
             loop {
-                // Check if we should stop
-                {
-                    let is_running = running.lock().await;
-                    if !*is_running {
+                for key in consumer.context().take_revoked() {
+                    paused.remove(&key);
+                    deliveries.remove(&key);
+                }
+
+                // Poll for messages until asked to stop; a message being handled is finished first
+                let next_resume = paused.values().min().copied();
+                let received = tokio::select! {
+                    _ = stopped.wait_for(|stop| *stop) => {
                         info!("Consumer stopped");
                         break;
                     }
-                }
+                    _ = sleep_until(next_resume) => {
+                        resume_due(&consumer, &mut paused);
+                        continue;
+                    }
+                    received = consumer.recv() => received,
+                };
 
-                // Poll for messages
-                match consumer.recv().await {
+                match received {
                     Ok(message) => {
-                        if let Some(payload) = message.payload() {
-                            match deserializer.deserialize(payload) {
-                                Ok(msg) => {
-                                    if let Err(e) = handler.handle(msg).await {
-                                        error!(?e, "Error handling message");
-                                    }
+                        let (topic, partition, offset) = (message.topic(), message.partition(), message.offset());
+                        let key = (topic.to_string(), partition);
+                        let delivery = match deliveries.get(&key) {
+                            Some(delivery) if delivery.offset == offset => *delivery,
+                            _ => Delivery { offset, retries: 0, redeliveries: 0 },
+                        };
+
+                        let outcome = dispatch(
+                            deserializer.as_ref(),
+                            handler.as_ref(),
+                            &retry,
+                            forwarder.as_deref(),
+                            &message,
+                            delivery.retries,
+                        )
+                        .await;
+
+                        let mut done = outcome == Outcome::Done;
+                        match outcome {
+                            Outcome::Done => {}
+                            Outcome::NotBefore(due) => {
+                                park(&consumer, &mut paused, &message, due);
+                            }
+                            Outcome::RetryAt(due) => {
+                                deliveries.insert(key.clone(), Delivery { retries: delivery.retries + 1, ..delivery });
+                                park(&consumer, &mut paused, &message, due);
+                            }
+                            Outcome::Failed if commit_after_handle => {
+                                let redeliveries = delivery.redeliveries + 1;
+                                if redeliveries > max_redeliveries {
+                                    error!(topic, partition, offset, attempts = redeliveries, "Giving up on message that keeps failing; skipping it");
+                                    done = true;
+                                } else {
+                                    // Park the partition so the failed message is delivered again later
+                                    warn!(topic, partition, offset, attempts = redeliveries, "Message failed; redelivering it");
+                                    deliveries.insert(key.clone(), Delivery { offset, retries: 0, redeliveries });
+                                    park(&consumer, &mut paused, &message, Utc::now() + REDELIVERY_DELAY);
                                 }
-                                Err(e) => {
-                                    error!(?e, "Error deserializing message");
+                            }
+                            // Without commit_after_handle a message that still fails is skipped
+                            Outcome::Failed => done = true,
+                        }
+
+                        if done {
+                            deliveries.remove(&key);
+                            if manual_offset_store {
+                                if let Err(e) = consumer.store_offset_from_message(&message) {
+                                    error!(?e, "Failed to store offset");
                                 }
                             }
                         }
@@ -151,24 +275,226 @@ where
                     }
                 }
             }
-        });
+        }));
 
         Ok(())
     }
 
+    /// Stop polling, wait for the message in flight to finish, then commit stored offsets
     async fn stop(&self) -> InfraResult<()> {
-        let mut running = self.running.lock().await;
-        *running = false;
         info!("Stopping Kafka consumer");
+        self.stop.send_replace(true);
+        if let Some(task) = self.task.lock().await.take() {
+            if let Err(e) = task.await {
+                error!(?e, "Kafka consumer task failed");
+            }
+        }
+        if self.commit_after_handle {
+            commit_stored_offsets(&self.consumer)?;
+        }
         Ok(())
     }
 
     async fn health_check(&self) -> InfraResult<()> {
-        let running = self.running.lock().await;
-        if *running {
-            Ok(())
-        } else {
-            Err(InfrastructureError::Kafka("Consumer not running".to_string()))
+        match self.task.lock().await.as_ref() {
+            Some(task) if !task.is_finished() => Ok(()),
+            _ => Err(InfrastructureError::Kafka("Consumer not running".to_string())),
+        }
+    }
+}
+
+/// Synchronously commit the offsets stored so far; having nothing to commit is not an error
+fn commit_stored_offsets(consumer: &StreamConsumer<RebalanceContext>) -> InfraResult<()> {
+    match consumer.commit_consumer_state(CommitMode::Sync) {
+        Ok(()) | Err(KafkaError::ConsumerCommit(RDKafkaErrorCode::NoOffset)) => Ok(()),
+        Err(e) => Err(InfrastructureError::Kafka(format!("Failed to commit offsets: {}", e))),
+    }
+}
+
+/// Envelope for a received message carrying `value`; non-UTF-8 header values are converted lossily
+fn consumed<T>(message: &BorrowedMessage<'_>, value: T) -> ConsumedMessage<T> {
+    let headers = message
+        .headers()
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|header| {
+                    let value = header.value.map(String::from_utf8_lossy).unwrap_or_default();
+                    (header.key.to_string(), value.into_owned())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    ConsumedMessage {
+        key: message.key().map(|key| String::from_utf8_lossy(key).into_owned()),
+        value,
+        headers,
+        topic: message.topic().to_string(),
+        partition: message.partition(),
+        offset: message.offset(),
+        timestamp: message.timestamp().to_millis().and_then(chrono::DateTime::from_timestamp_millis),
+    }
+}
+
+/// Consumer context collecting revoked partitions, whose pause and retry state the poll
+/// loop must forget
+#[derive(Default)]
+struct RebalanceContext {
+    revoked: SyncMutex<Vec<(String, i32)>>,
+}
+
+impl RebalanceContext {
+    fn take_revoked(&self) -> Vec<(String, i32)> {
+        self.revoked.lock().map(|mut revoked| std::mem::take(&mut *revoked)).unwrap_or_default()
+    }
+}
+
+impl ClientContext for RebalanceContext {}
+
+impl ConsumerContext for RebalanceContext {
+    fn post_rebalance(&self, rebalance: &Rebalance<'_>) {
+        if let (Rebalance::Revoke(partitions), Ok(mut revoked)) = (rebalance, self.revoked.lock()) {
+            revoked.extend(partitions.elements().iter().map(|e| (e.topic().to_string(), e.partition())));
+        }
+    }
+}
+
+/// Failed attempts at the message a partition is stuck on
+#[derive(Debug, Clone, Copy)]
+struct Delivery {
+    offset: i64,
+    /// In-place retries made so far
+    retries: u32,
+    /// Times the message was redelivered after exhausting its retries
+    redeliveries: u32,
+}
+
+/// Result of dispatching one message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// Handled, skipped or forwarded; its offset can be stored
+    Done,
+    /// Still failing and not forwarded
+    Failed,
+    /// Retry-topic message that must not be handled before this time
+    NotBefore(DateTime<Utc>),
+    /// Handler failed; retry it in place at this time
+    RetryAt(DateTime<Utc>),
+}
+
+/// Handle one message under `retry`, `retries` in-place retries having been made already
+async fn dispatch<T, D, H>(
+    deserializer: &D,
+    handler: &H,
+    retry: &RetryPolicy,
+    forwarder: Option<&RetryForwarder>,
+    message: &BorrowedMessage<'_>,
+    retries: u32,
+) -> Outcome
+where
+    T: Send + 'static,
+    D: MessageDeserializer<T>,
+    H: MessageHandler<T>,
+{
+    let Some(payload) = message.payload() else { return Outcome::Done };
+
+    if let Some(not_before) = retry_policy::not_before(message) {
+        if not_before > Utc::now() {
+            return Outcome::NotBefore(not_before);
+        }
+    }
+
+    let previous_attempts = retry_policy::previous_attempts(message);
+    let msg = match deserializer.deserialize(payload) {
+        Ok(msg) => msg,
+        Err(e) => {
+            error!(?e, "Error deserializing message");
+            let Some(dead_letter_topic) = retry.dead_letter_topic.as_deref() else { return Outcome::Done };
+            return forward(forwarder, dead_letter_topic, message, previous_attempts + 1, &e, None).await;
+        }
+    };
+    let error = match handler.handle_message(consumed(message, msg)).await {
+        Ok(()) => return Outcome::Done,
+        Err(e) if retries < retry.max_retries => {
+            warn!(?e, retry = retries + 1, "Error handling message, retrying");
+            let backoff = chrono::Duration::from_std(retry.backoff(retries + 1)).unwrap_or(chrono::Duration::MAX);
+            return Outcome::RetryAt(Utc::now() + backoff);
+        }
+        Err(e) => e,
+    };
+
+    error!(?error, "Error handling message");
+    match retry.next_topic(message.topic()) {
+        Some((topic, delay)) => {
+            forward(forwarder, topic, message, previous_attempts + retries + 1, &error, delay).await
+        }
+        None => Outcome::Failed,
+    }
+}
+
+async fn forward(
+    forwarder: Option<&RetryForwarder>,
+    topic: &str,
+    message: &BorrowedMessage<'_>,
+    attempts: u32,
+    error: &InfrastructureError,
+    delay: Option<Duration>,
+) -> Outcome {
+    let Some(forwarder) = forwarder else { return Outcome::Failed };
+    match forwarder.forward(topic, message, attempts, &error.to_string(), delay).await {
+        Ok(()) => {
+            info!(topic, attempts, "Forwarded failed message");
+            Outcome::Done
+        }
+        Err(e) => {
+            error!(?e, "Failed to forward message");
+            Outcome::Failed
+        }
+    }
+}
+
+/// Sleep until `at`, or forever when nothing is scheduled
+async fn sleep_until(at: Option<DateTime<Utc>>) {
+    match at {
+        Some(at) => tokio::time::sleep((at - Utc::now()).to_std().unwrap_or_default()).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Pause the partition of `message` and rewind to it, so it is delivered again once
+/// resumed at `due`
+fn park(
+    consumer: &StreamConsumer<RebalanceContext>,
+    paused: &mut HashMap<(String, i32), DateTime<Utc>>,
+    message: &BorrowedMessage<'_>,
+    due: DateTime<Utc>,
+) {
+    let (topic, partition, offset) = (message.topic(), message.partition(), message.offset());
+    let mut assignment = TopicPartitionList::new();
+    assignment.add_partition(topic, partition);
+    if let Err(e) = consumer.pause(&assignment) {
+        error!(?e, topic, partition, "Failed to pause partition");
+    }
+    if let Err(e) = consumer.seek(topic, partition, Offset::Offset(offset), Duration::from_secs(5)) {
+        error!(?e, topic, partition, "Failed to rewind parked message");
+    }
+    paused.insert((topic.to_string(), partition), due);
+}
+
+/// Resume the paused partitions whose next message is due
+fn resume_due(consumer: &StreamConsumer<RebalanceContext>, paused: &mut HashMap<(String, i32), DateTime<Utc>>) {
+    let now = Utc::now();
+    let mut due = TopicPartitionList::new();
+    paused.retain(|(topic, partition), at| {
+        let keep = *at > now;
+        if !keep {
+            due.add_partition(topic, *partition);
+        }
+        keep
+    });
+    if due.count() > 0 {
+        if let Err(e) = consumer.resume(&due) {
+            error!(?e, "Failed to resume partitions");
         }
     }
 }
@@ -1,3 +1,5 @@
 pub mod base_consumer;
+pub mod retry_policy;
 
 pub use base_consumer::{KafkaConsumer, KafkaConsumerPort, MessageHandler};
+pub use retry_policy::RetryPolicy;
@@ -0,0 +1,168 @@
+use std::time::Duration;
+use chrono::{DateTime, TimeZone, Utc};
+use rdkafka::{
+    message::{BorrowedMessage, Header, Headers, OwnedHeaders},
+    producer::{FutureProducer, FutureRecord},
+    ClientConfig, Message,
+};
+use crate::infrastructure::messaging::kafka::common::{ORIGINAL_OFFSET_HEADER, ORIGINAL_PARTITION_HEADER, ORIGINAL_TOPIC_HEADER};
+use crate::infrastructure::messaging::kafka::config::{KafkaConsumerConfig, RetryTopic};
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// Total handling attempts made so far, across the original and retry topics
+pub const RETRY_ATTEMPTS_HEADER: &str = "retry-attempts";
+/// Milliseconds since the epoch before which a retry-topic message must not be handled
+pub const RETRY_NOT_BEFORE_HEADER: &str = "retry-not-before";
+/// Error of the last failed attempt
+pub const ERROR_HEADER: &str = "error";
+
+/// What a consumer does when handling a message fails
+///
+/// The handler is retried in place `max_retries` times with exponential backoff. If it
+/// still fails, the original bytes are forwarded to the next retry topic (consumed again
+/// after that topic's delay) and, once those are exhausted, to the dead-letter topic.
+/// Messages that cannot be deserialized go straight to the dead-letter topic.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub retry_topics: Vec<RetryTopic>,
+    pub dead_letter_topic: Option<String>,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &KafkaConsumerConfig) -> Self {
+        Self {
+            max_retries: config.max_retries(),
+            initial_backoff: config.retry_backoff(),
+            max_backoff: config.max_retry_backoff(),
+            retry_topics: config.retry_topics.clone(),
+            dead_letter_topic: config.dead_letter_topic.clone(),
+        }
+    }
+
+    /// Delay before in-place retry number `retry` (1-based)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Whether failed messages are forwarded to other topics at all
+    pub fn forwards(&self) -> bool {
+        !self.retry_topics.is_empty() || self.dead_letter_topic.is_some()
+    }
+
+    /// Retry topic or dead-letter topic a message that failed on `topic` goes to next
+    pub fn next_topic(&self, topic: &str) -> Option<(&str, Option<Duration>)> {
+        let stage = self.retry_topics.iter().position(|retry| retry.topic == topic).map_or(0, |i| i + 1);
+        match self.retry_topics.get(stage) {
+            Some(retry) => Some((retry.topic.as_str(), Some(retry.delay()))),
+            None => self.dead_letter_topic.as_deref().map(|topic| (topic, None)),
+        }
+    }
+}
+
+/// Republishes failed messages to retry and dead-letter topics
+pub(crate) struct RetryForwarder {
+    producer: FutureProducer,
+}
+
+impl RetryForwarder {
+    pub(crate) fn new(config: &KafkaConsumerConfig) -> InfraResult<Self> {
+        let mut client_config = ClientConfig::new();
+        client_config.set("bootstrap.servers", &config.brokers);
+        if let Some(client_id) = &config.client_id {
+            client_config.set("client.id", format!("{}-retry", client_id));
+        }
+        let producer = client_config
+            .create()
+            .map_err(|e| InfrastructureError::Kafka(format!("Failed to create retry producer: {}", e)))?;
+        Ok(Self { producer })
+    }
+
+    /// Forward the original key, payload and headers to `topic`, adding retry headers
+    pub(crate) async fn forward(
+        &self,
+        topic: &str,
+        message: &BorrowedMessage<'_>,
+        attempts: u32,
+        error: &str,
+        delay: Option<Duration>,
+    ) -> InfraResult<()> {
+        let not_before = delay
+            .and_then(|delay| chrono::Duration::from_std(delay).ok())
+            .map(|delay| Utc::now() + delay);
+        let headers = retry_headers(message, attempts, error, not_before);
+
+        let mut record = FutureRecord::<[u8], [u8]>::to(topic).headers(headers);
+        if let Some(key) = message.key() {
+            record = record.key(key);
+        }
+        if let Some(payload) = message.payload() {
+            record = record.payload(payload);
+        }
+        self.producer
+            .send(record, Duration::from_secs(0))
+            .await
+            .map(|_| ())
+            .map_err(|(e, _)| InfrastructureError::Kafka(format!("Failed to forward message to {}: {}", topic, e)))
+    }
+}
+
+/// Headers for forwarding `message`: its own headers minus stale retry headers, its
+/// original position (unless already recorded by an earlier forward) and fresh retry headers
+pub fn retry_headers<M: Message>(
+    message: &M,
+    attempts: u32,
+    error: &str,
+    not_before: Option<DateTime<Utc>>,
+) -> OwnedHeaders {
+    let mut headers = OwnedHeaders::new();
+    let mut has_original = false;
+    if let Some(existing) = message.headers() {
+        for header in existing.iter() {
+            if [RETRY_ATTEMPTS_HEADER, RETRY_NOT_BEFORE_HEADER, ERROR_HEADER].contains(&header.key) {
+                continue;
+            }
+            has_original |= header.key == ORIGINAL_TOPIC_HEADER;
+            headers = headers.insert(header);
+        }
+    }
+    if !has_original {
+        headers = insert(headers, ORIGINAL_TOPIC_HEADER, message.topic());
+        headers = insert(headers, ORIGINAL_PARTITION_HEADER, &message.partition().to_string());
+        headers = insert(headers, ORIGINAL_OFFSET_HEADER, &message.offset().to_string());
+    }
+    headers = insert(headers, RETRY_ATTEMPTS_HEADER, &attempts.to_string());
+    headers = insert(headers, ERROR_HEADER, error);
+    if let Some(not_before) = not_before {
+        headers = insert(headers, RETRY_NOT_BEFORE_HEADER, &not_before.timestamp_millis().to_string());
+    }
+    headers
+}
+
+fn insert(headers: OwnedHeaders, key: &str, value: &str) -> OwnedHeaders {
+    headers.insert(Header { key, value: Some(value.as_bytes()) })
+}
+
+/// Value of a UTF-8 header
+fn header<'a, M: Message>(message: &'a M, key: &str) -> Option<&'a str> {
+    message
+        .headers()?
+        .iter()
+        .find(|header| header.key == key)
+        .and_then(|header| header.value)
+        .and_then(|value| std::str::from_utf8(value).ok())
+}
+
+/// Handling attempts recorded on a forwarded message (0 for a fresh one)
+pub fn previous_attempts<M: Message>(message: &M) -> u32 {
+    header(message, RETRY_ATTEMPTS_HEADER).and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
+/// Time before which a retry-topic message must not be handled
+pub fn not_before<M: Message>(message: &M) -> Option<DateTime<Utc>> {
+    let millis = header(message, RETRY_NOT_BEFORE_HEADER)?.parse().ok()?;
+    Utc.timestamp_millis_opt(millis).single()
+}
@@ -3,7 +3,7 @@ pub mod consumers;
 pub mod config;
 pub mod common;
 
-pub use config::{KafkaProducerConfig, KafkaConsumerConfig};
-pub use common::{KafkaMessage, SerializationFormat};
+pub use config::{KafkaProducerConfig, KafkaConsumerConfig, RetryTopic};
+pub use common::{ConsumedMessage, KafkaMessage, SerializationFormat};
 pub use producers::{KafkaProducer, KafkaProducerPort, BatchingKafkaProducer};
-pub use consumers::{KafkaConsumer, KafkaConsumerPort, MessageHandler};
+pub use consumers::{KafkaConsumer, KafkaConsumerPort, MessageHandler, RetryPolicy};
@@ -14,6 +14,12 @@ where
     T: Send + 'static,
 {
     async fn send(&self, message: KafkaMessage<T>) -> InfraResult<()>;
+    /// Send and wait until the broker has acknowledged the message.
+    /// The default sends and then flushes.
+    async fn send_confirmed(&self, message: KafkaMessage<T>) -> InfraResult<()> {
+        self.send(message).await?;
+        self.flush().await
+    }
     async fn send_batch(&self, messages: Vec<KafkaMessage<T>>) -> InfraResult<()> {
         for msg in messages.into_iter() {
             self.send(msg).await?;
@@ -36,6 +42,8 @@ where
     topic: String,
     tx: mpsc::Sender<KafkaMessage<T>>,
     serializer: Arc<S>,
+    max_retry: u32,
+    backoff: Duration,
 }
 
 impl<T, S> KafkaProducer<T, S>
@@ -103,15 +111,15 @@ where
                         topic: topic.clone(),
                         tx,
                         serializer: serializer.clone(),
+                        max_retry: config.max_retry_attempts(),
+                        backoff: config.retry_backoff(),
                     });
 
                     let arc_clone = arc.clone();
-                    let max_retry = config.max_retry_attempts();
-                    let backoff = config.retry_backoff();
 
                     tokio::spawn(async move {
                         while let Some(msg) = rx.recv().await {
-                            if let Err(e) = send_with_retry(&arc_clone, msg, max_retry, backoff).await {
+                            if let Err(e) = send_with_retry(&arc_clone, msg).await {
                                 error!(?e, "Kafka publish failed after retries");
                             }
                         }
@@ -152,10 +160,8 @@ where
 }
 
 async fn send_with_retry<T, S>(
-    producer: &Arc<KafkaProducer<T, S>>,
+    producer: &KafkaProducer<T, S>,
     message: KafkaMessage<T>,
-    max_retry: u32,
-    backoff: Duration,
 ) -> InfraResult<()>
 where
     S: MessageSerializer<T>,
@@ -163,29 +169,30 @@ where
     let payload = producer.serializer.serialize(&message.value)?;
     let key = message.key.as_deref().unwrap_or("");
 
-    for attempt in 0..=max_retry {
-        let mut record = FutureRecord::to(&producer.topic)
+    // Add headers if present
+    let headers = message.headers.iter().fold(
+        rdkafka::message::OwnedHeaders::new_with_capacity(message.headers.len()),
+        |headers, (header_key, header_value)| {
+            headers.insert(rdkafka::message::Header {
+                key: header_key,
+                value: Some(header_value.as_bytes()),
+            })
+        },
+    );
+
+    for attempt in 0..=producer.max_retry {
+        let record = FutureRecord::to(&producer.topic)
             .payload(&payload)
-            .key(key);
-
-        // Add headers if present
-        for (header_key, header_value) in &message.headers {
-            record = record.headers(
-                rdkafka::message::OwnedHeaders::new()
-                    .insert(rdkafka::message::Header {
-                        key: header_key,
-                        value: Some(header_value.as_bytes()),
-                    })
-            );
-        }
+            .key(key)
+            .headers(headers.clone());
 
         match producer.producer.send(record, Duration::from_secs(0)).await {
             Ok(_) => return Ok(()),
             Err((e, _)) => {
-                if attempt == max_retry {
+                if attempt == producer.max_retry {
                     return Err(InfrastructureError::Kafka(format!("Send error: {}", e)));
                 }
-                tokio::time::sleep(backoff).await;
+                tokio::time::sleep(producer.backoff).await;
             }
         }
     }
@@ -205,6 +212,11 @@ where
             .map_err(|e| InfrastructureError::Kafka(format!("Queue send error: {}", e)))
     }
 
+    async fn send_confirmed(&self, message: KafkaMessage<T>) -> InfraResult<()> {
+        // Bypass the queue so the caller learns about the delivery outcome
+        send_with_retry(self, message).await
+    }
+
     async fn flush(&self) -> InfraResult<()> {
         let _ = self.producer.flush(Duration::from_secs(5));
         Ok(())
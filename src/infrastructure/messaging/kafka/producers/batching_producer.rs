@@ -100,6 +100,12 @@ where
         Ok(())
     }
 
+    async fn send_confirmed(&self, message: KafkaMessage<T>) -> InfraResult<()> {
+        // Deliver buffered messages first to keep ordering
+        self.flush_buffer().await?;
+        self.inner.send_confirmed(message).await
+    }
+
     async fn send_batch(&self, messages: Vec<KafkaMessage<T>>) -> InfraResult<()> {
         for msg in messages {
             self.send(msg).await?;
@@ -1,7 +1,10 @@
 pub mod bootstrap;
 pub mod config;
 pub mod event_store;
+pub mod inbox;
 pub mod messaging;
+pub mod outbox;
+pub mod projection_store;
 pub mod repositories;
 pub mod shutdown;
 pub mod startup;
@@ -0,0 +1,5 @@
+pub mod outbox_relay;
+pub mod postgres_outbox_store;
+
+pub use outbox_relay::OutboxRelay;
+pub use postgres_outbox_store::PostgresOutboxStore;
@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet};
+use std::{sync::Arc, time::Duration};
+use serde_json::Value;
+use tokio::sync::watch;
+use tracing::{debug, error, info, warn};
+use crate::application::ports::outbox_store::{OutboxMessage, OutboxStore};
+use crate::infrastructure::messaging::kafka::common::{KafkaMessage, MESSAGE_ID_HEADER};
+use crate::infrastructure::messaging::kafka::producers::KafkaProducerPort;
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// Publishes outbox messages to Kafka with at-least-once delivery
+///
+/// A message is marked sent only after `send_confirmed` succeeds, so a crash in between
+/// republishes it; consumers deduplicate on the `message-id` header. After a failure, later
+/// messages in the batch with the same topic and key are released rather than published
+/// ahead of it, while the rest of the batch goes on; the failed message is retried with
+/// exponential backoff and given up after `max_attempts`. Messages without a key are not
+/// ordered.
+pub struct OutboxRelay {
+    store: Arc<dyn OutboxStore>,
+    producers: HashMap<String, Arc<dyn KafkaProducerPort<Value>>>,
+    batch_size: usize,
+    poll_interval: Duration,
+    lease: Duration,
+    max_attempts: u32,
+    base_backoff: Duration,
+    max_backoff: Duration,
+    stop: watch::Sender<bool>,
+}
+
+impl OutboxRelay {
+    pub fn new(store: Arc<dyn OutboxStore>) -> Self {
+        Self {
+            store,
+            producers: HashMap::new(),
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+            lease: Duration::from_secs(30),
+            max_attempts: 10,
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            stop: watch::channel(false).0,
+        }
+    }
+
+    /// Producer for messages with this topic
+    pub fn with_producer(mut self, topic: impl Into<String>, producer: Arc<dyn KafkaProducerPort<Value>>) -> Self {
+        self.producers.insert(topic.into(), producer);
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Delay between polls once the outbox is drained
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// How long claimed messages stay reserved for this relay
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Attempts before a message is given up and left for manual inspection
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// First retry delay, doubled per attempt up to `max_backoff`
+    pub fn with_backoff(mut self, base_backoff: Duration, max_backoff: Duration) -> Self {
+        self.base_backoff = base_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Publish one batch of due messages, returning how many were sent
+    pub async fn relay_batch(&self) -> InfraResult<usize> {
+        let messages = self.store.claim(self.batch_size, self.lease).await?;
+        let mut sent = 0;
+        // (topic, key) of messages that failed and will be retried
+        let mut blocked: HashSet<(&str, &str)> = HashSet::new();
+        let mut held = Vec::new();
+
+        for message in &messages {
+            let ordering_key = message.key.as_deref().map(|key| (message.topic.as_str(), key));
+            if ordering_key.is_some_and(|ordering_key| blocked.contains(&ordering_key)) {
+                held.push(message.id);
+                continue;
+            }
+
+            match self.publish(message).await {
+                Ok(()) => {
+                    self.store.mark_sent(message.id).await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    let attempts = message.attempts + 1;
+                    if attempts >= self.max_attempts {
+                        error!(id = %message.id, topic = %message.topic, attempts, error = %e, "Giving up on outbox message");
+                        self.store.mark_failed(message.id, &e.to_string(), None).await?;
+                        continue;
+                    }
+
+                    let retry_in = self.backoff(attempts);
+                    warn!(id = %message.id, topic = %message.topic, attempts, retry_in_ms = retry_in.as_millis(), error = %e, "Outbox publish failed");
+                    self.store.mark_failed(message.id, &e.to_string(), Some(retry_in)).await?;
+                    blocked.extend(ordering_key);
+                }
+            }
+        }
+
+        if !held.is_empty() {
+            self.store.release(&held).await?;
+        }
+
+        if sent > 0 {
+            debug!(sent, "Outbox messages relayed");
+        }
+        Ok(sent)
+    }
+
+    /// Relay until `stop` is called, polling when the outbox is empty
+    pub async fn run(&self) {
+        let mut stop = self.stop.subscribe();
+        info!(topics = self.producers.len(), "Starting outbox relay");
+
+        loop {
+            let idle = match self.relay_batch().await {
+                Ok(sent) => sent == 0,
+                Err(e) => {
+                    error!(error = %e, "Outbox relay batch failed");
+                    true
+                }
+            };
+            if idle {
+                tokio::select! {
+                    _ = stop.wait_for(|stopped| *stopped) => break,
+                    _ = tokio::time::sleep(self.poll_interval) => {}
+                }
+            } else if *stop.borrow() {
+                break;
+            }
+        }
+
+        info!("Outbox relay stopped");
+    }
+
+    pub fn stop(&self) {
+        self.stop.send_replace(true);
+    }
+
+    async fn publish(&self, message: &OutboxMessage) -> InfraResult<()> {
+        let producer = self.producers.get(&message.topic).ok_or_else(|| {
+            InfrastructureError::Messaging(format!("no producer for topic {}", message.topic))
+        })?;
+
+        let mut kafka_message = KafkaMessage::new(message.payload.clone())
+            .with_header(MESSAGE_ID_HEADER.to_string(), message.id.to_string());
+        for (key, value) in &message.headers {
+            kafka_message = kafka_message.with_header(key.clone(), value.clone());
+        }
+        if let Some(key) = &message.key {
+            kafka_message = kafka_message.with_key(key.clone());
+        }
+        producer.send_confirmed(kafka_message).await
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.base_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+use crate::application::ports::outbox_store::{OutboxMessage, OutboxStore};
+use crate::infrastructure::repositories::UnitOfWork;
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+type OutboxRow = (Uuid, String, Option<String>, Value, Json<Vec<(String, String)>>, DateTime<Utc>, i32, i64);
+
+/// PostgreSQL outbox (see `migrations/V009__outbox.sql`)
+///
+/// `enqueue` writes through a `UnitOfWork`, so the message is stored if and only if the
+/// domain changes in the same unit of work commit. `claim` holds back a message while an
+/// earlier one with the same topic and key is waiting for a retry or leased by another
+/// relay, so messages for one key are published in order.
+#[derive(Clone)]
+pub struct PostgresOutboxStore {
+    pool: PgPool,
+}
+
+impl PostgresOutboxStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Record a message in `uow`'s transaction
+    pub async fn enqueue(&self, uow: &UnitOfWork, message: &OutboxMessage) -> InfraResult<()> {
+        let mut conn = uow.connection().await?;
+        insert(&mut conn, message).await
+    }
+
+    /// Record several messages in `uow`'s transaction, published in this order
+    pub async fn enqueue_all(&self, uow: &UnitOfWork, messages: &[OutboxMessage]) -> InfraResult<()> {
+        let mut conn = uow.connection().await?;
+        for message in messages {
+            insert(&mut conn, message).await?;
+        }
+        Ok(())
+    }
+
+    /// Delete messages sent more than `older_than` ago, returning how many were removed
+    pub async fn purge_sent(&self, older_than: Duration) -> InfraResult<u64> {
+        let result = sqlx::query("DELETE FROM outbox WHERE sent_at < now() - make_interval(secs => $1)")
+            .bind(older_than.as_secs_f64())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected())
+    }
+}
+
+async fn insert(conn: &mut PgConnection, message: &OutboxMessage) -> InfraResult<()> {
+    sqlx::query(
+        "INSERT INTO outbox (id, topic, message_key, payload, headers, created_at) \
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(message.id)
+    .bind(&message.topic)
+    .bind(&message.key)
+    .bind(&message.payload)
+    .bind(Json(&message.headers))
+    .bind(message.created_at)
+    .execute(conn)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+#[async_trait]
+impl OutboxStore for PostgresOutboxStore {
+    async fn claim(&self, limit: usize, lease: Duration) -> InfraResult<Vec<OutboxMessage>> {
+        let mut rows: Vec<OutboxRow> = sqlx::query_as(
+            "UPDATE outbox SET locked_until = now() + make_interval(secs => $2) \
+             WHERE id IN ( \
+                 SELECT id FROM outbox AS candidate \
+                 WHERE sent_at IS NULL AND failed_at IS NULL AND next_attempt_at <= now() \
+                   AND (locked_until IS NULL OR locked_until <= now()) \
+                   AND NOT EXISTS ( \
+                       SELECT 1 FROM outbox AS earlier \
+                       WHERE earlier.topic = candidate.topic AND earlier.message_key = candidate.message_key \
+                         AND earlier.seq < candidate.seq AND earlier.sent_at IS NULL AND earlier.failed_at IS NULL \
+                         AND (earlier.next_attempt_at > now() OR earlier.locked_until > now())) \
+                 ORDER BY seq LIMIT $1 FOR UPDATE SKIP LOCKED) \
+             RETURNING id, topic, message_key, payload, headers, created_at, attempts, seq",
+        )
+        .bind(limit.min(i64::MAX as usize) as i64)
+        .bind(lease.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.sort_by_key(|row| row.7);
+        Ok(rows
+            .into_iter()
+            .map(|(id, topic, key, payload, Json(headers), created_at, attempts, _)| OutboxMessage {
+                id,
+                topic,
+                key,
+                payload,
+                headers,
+                created_at,
+                attempts: attempts as u32,
+            })
+            .collect())
+    }
+
+    async fn mark_sent(&self, id: Uuid) -> InfraResult<()> {
+        sqlx::query("UPDATE outbox SET sent_at = now(), locked_until = NULL WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: Uuid, error: &str, retry_in: Option<Duration>) -> InfraResult<()> {
+        sqlx::query(
+            "UPDATE outbox SET attempts = attempts + 1, last_error = $2, locked_until = NULL, \
+             next_attempt_at = COALESCE(now() + make_interval(secs => $3), next_attempt_at), \
+             failed_at = CASE WHEN $3 IS NULL THEN now() END \
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_in.map(|delay| delay.as_secs_f64()))
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn release(&self, ids: &[Uuid]) -> InfraResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        sqlx::query("UPDATE outbox SET locked_until = NULL WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+fn db_error(e: sqlx::Error) -> InfrastructureError {
+    InfrastructureError::Database(e.to_string())
+}
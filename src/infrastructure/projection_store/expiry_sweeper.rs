@@ -0,0 +1,59 @@
+use std::{sync::Arc, time::Duration};
+use tokio::sync::watch;
+use tracing::{debug, error, info};
+use crate::application::ports::projection_store::ProjectionStore;
+use crate::shared::errors::InfraResult;
+
+/// Background task that periodically removes expired projections
+///
+/// Expired entries are already invisible to reads; sweeping only reclaims their space.
+pub struct ExpirySweeper {
+    store: Arc<dyn ProjectionStore>,
+    interval: Duration,
+    stop: watch::Sender<bool>,
+}
+
+impl ExpirySweeper {
+    pub fn new(store: Arc<dyn ProjectionStore>) -> Self {
+        Self {
+            store,
+            interval: Duration::from_secs(60),
+            stop: watch::channel(false).0,
+        }
+    }
+
+    /// Time between two sweeps
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Purge expired entries once, returning how many were removed
+    pub async fn sweep(&self) -> InfraResult<u64> {
+        let removed = self.store.purge_expired().await?;
+        debug!(removed, "Expired projections purged");
+        Ok(removed)
+    }
+
+    /// Sweep every interval until `stop` is called; failed sweeps are logged and retried next round
+    pub async fn run(&self) {
+        let mut stop = self.stop.subscribe();
+        info!(interval = ?self.interval, "Starting projection expiry sweeper");
+
+        loop {
+            tokio::select! {
+                _ = stop.wait_for(|stopped| *stopped) => break,
+                _ = tokio::time::sleep(self.interval) => {}
+            }
+            if let Err(e) = self.sweep().await {
+                error!(error = %e, "Projection expiry sweep failed");
+            }
+        }
+
+        info!("Projection expiry sweeper stopped");
+    }
+
+    pub fn stop(&self) {
+        self.stop.send_replace(true);
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+use crate::application::ports::projection_store::{
+    CheckpointStore, ProjectionEntry, ProjectionRevision, ProjectionStore, ProjectionWrite, VersionedEntry,
+};
+use crate::shared::errors::{InfraResult, InfrastructureError};
+use crate::shared::types::EventPosition;
+
+#[derive(Default)]
+struct State {
+    entries: BTreeMap<String, VersionedEntry>,
+    checkpoints: HashMap<String, EventPosition>,
+}
+
+impl State {
+    /// Entry for `key` unless it is missing or expired
+    fn live(&self, key: &str, now: DateTime<Utc>) -> Option<&VersionedEntry> {
+        self.entries.get(key).filter(|entry| is_live(entry, now))
+    }
+
+    fn put(&mut self, key: String, value: Vec<u8>, expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> ProjectionRevision {
+        let revision = self.live(&key, now).map_or(1, |entry| entry.revision + 1);
+        self.entries.insert(key, VersionedEntry { value, revision, expires_at });
+        revision
+    }
+}
+
+/// In-memory projection store for tests and local runs; clones share storage
+#[derive(Clone, Default)]
+pub struct InMemoryProjectionStore {
+    state: Arc<RwLock<State>>,
+}
+
+impl InMemoryProjectionStore {
+    pub fn new() -> Self { Self::default() }
+
+    /// Number of stored projections, including expired ones not purged yet
+    pub async fn len(&self) -> usize {
+        self.state.read().await.entries.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.state.read().await.entries.is_empty()
+    }
+
+    /// Remove every projection and checkpoint
+    pub async fn clear(&self) {
+        *self.state.write().await = State::default();
+    }
+}
+
+#[async_trait]
+impl ProjectionStore for InMemoryProjectionStore {
+    async fn save(&self, key: &str, data: &[u8]) -> InfraResult<()> {
+        self.state.write().await.put(key.to_string(), data.to_vec(), None, Utc::now());
+        Ok(())
+    }
+
+    async fn save_with_ttl(&self, key: &str, data: &[u8], ttl: Duration) -> InfraResult<()> {
+        let now = Utc::now();
+        self.state.write().await.put(key.to_string(), data.to_vec(), Some(expiry(now, ttl)?), now);
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> InfraResult<Option<Vec<u8>>> {
+        Ok(self.state.read().await.live(key, Utc::now()).map(|entry| entry.value.clone()))
+    }
+
+    async fn get_versioned(&self, key: &str) -> InfraResult<Option<VersionedEntry>> {
+        Ok(self.state.read().await.live(key, Utc::now()).cloned())
+    }
+
+    async fn delete(&self, key: &str) -> InfraResult<()> {
+        self.state.write().await.entries.remove(key);
+        Ok(())
+    }
+
+    async fn compare_and_set(
+        &self,
+        key: &str,
+        expected: Option<ProjectionRevision>,
+        data: &[u8],
+        ttl: Option<Duration>,
+    ) -> InfraResult<ProjectionRevision> {
+        let now = Utc::now();
+        let expires_at = ttl.map(|ttl| expiry(now, ttl)).transpose()?;
+        let mut guard = self.state.write().await;
+        let current = guard.live(key, now).map(|entry| entry.revision);
+        if current != expected {
+            return Err(revision_conflict(key, current, expected));
+        }
+        Ok(guard.put(key.to_string(), data.to_vec(), expires_at, now))
+    }
+
+    async fn compare_and_delete(&self, key: &str, expected: ProjectionRevision) -> InfraResult<()> {
+        let mut guard = self.state.write().await;
+        let current = guard.live(key, Utc::now()).map(|entry| entry.revision);
+        if current != Some(expected) {
+            return Err(revision_conflict(key, current, Some(expected)));
+        }
+        guard.entries.remove(key);
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> InfraResult<u64> {
+        let now = Utc::now();
+        let mut guard = self.state.write().await;
+        let before = guard.entries.len();
+        guard.entries.retain(|_, entry| is_live(entry, now));
+        Ok((before - guard.entries.len()) as u64)
+    }
+
+    async fn scan_prefix(&self, prefix: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>> {
+        let now = Utc::now();
+        let guard = self.state.read().await;
+        Ok(guard
+            .entries
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .filter(|(_, entry)| is_live(entry, now))
+            .take(limit)
+            .map(|(key, entry)| (key.clone(), entry.value.clone()))
+            .collect())
+    }
+
+    async fn scan_range(&self, start: &str, end: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>> {
+        if start >= end {
+            return Ok(Vec::new());
+        }
+        let now = Utc::now();
+        let guard = self.state.read().await;
+        Ok(guard
+            .entries
+            .range(start.to_string()..end.to_string())
+            .filter(|(_, entry)| is_live(entry, now))
+            .take(limit)
+            .map(|(key, entry)| (key.clone(), entry.value.clone()))
+            .collect())
+    }
+
+    async fn get_many(&self, keys: &[String]) -> InfraResult<Vec<Option<Vec<u8>>>> {
+        let now = Utc::now();
+        let guard = self.state.read().await;
+        Ok(keys.iter().map(|key| guard.live(key, now).map(|entry| entry.value.clone())).collect())
+    }
+
+    async fn save_many(&self, entries: Vec<ProjectionEntry>) -> InfraResult<()> {
+        let now = Utc::now();
+        let mut guard = self.state.write().await;
+        for (key, data) in entries {
+            guard.put(key, data, None, now);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for InMemoryProjectionStore {
+    async fn load_checkpoint(&self, projection: &str) -> InfraResult<EventPosition> {
+        Ok(self.state.read().await.checkpoints.get(projection).copied().unwrap_or(0))
+    }
+
+    async fn commit(&self, projection: &str, from: EventPosition, to: EventPosition, writes: Vec<ProjectionWrite>) -> InfraResult<()> {
+        let now = Utc::now();
+        let mut guard = self.state.write().await;
+        let current = guard.checkpoints.get(projection).copied().unwrap_or(0);
+        if current != from {
+            return Err(InfrastructureError::Concurrency(format!(
+                "projection {projection} checkpoint is at {current}, expected {from}"
+            )));
+        }
+        for write in writes {
+            match write {
+                ProjectionWrite::Save(key, data) => { guard.put(key, data, None, now); }
+                ProjectionWrite::Delete(key) => { guard.entries.remove(&key); }
+            }
+        }
+        guard.checkpoints.insert(projection.to_string(), to);
+        Ok(())
+    }
+}
+
+fn is_live(entry: &VersionedEntry, now: DateTime<Utc>) -> bool {
+    entry.expires_at.is_none_or(|at| at > now)
+}
+
+fn expiry(now: DateTime<Utc>, ttl: Duration) -> InfraResult<DateTime<Utc>> {
+    chrono::Duration::from_std(ttl)
+        .ok()
+        .and_then(|ttl| now.checked_add_signed(ttl))
+        .ok_or_else(|| InfrastructureError::InvalidQuery(format!("projection TTL out of range: {ttl:?}")))
+}
+
+fn revision_conflict(key: &str, current: Option<ProjectionRevision>, expected: Option<ProjectionRevision>) -> InfrastructureError {
+    InfrastructureError::Concurrency(format!(
+        "projection {key} is at revision {current:?}, expected {expected:?}"
+    ))
+}
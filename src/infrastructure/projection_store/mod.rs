@@ -0,0 +1,9 @@
+pub mod expiry_sweeper;
+pub mod in_memory_projection_store;
+pub mod postgres_projection_store;
+pub mod typed_projection_store;
+
+pub use expiry_sweeper::ExpirySweeper;
+pub use in_memory_projection_store::InMemoryProjectionStore;
+pub use postgres_projection_store::PostgresProjectionStore;
+pub use typed_projection_store::{TypedEntry, TypedProjectionStore};
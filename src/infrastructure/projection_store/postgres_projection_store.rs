@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use crate::application::ports::projection_store::{
+    CheckpointStore, ProjectionEntry, ProjectionRevision, ProjectionStore, ProjectionWrite, VersionedEntry,
+};
+use crate::shared::errors::{InfraResult, InfrastructureError};
+use crate::shared::types::EventPosition;
+
+/// Upsert bumping the revision, or restarting it at 1 over an expired row.
+/// Binds: key, value, TTL in seconds (NULL for no expiry).
+const UPSERT: &str = "INSERT INTO projections (key, value, expires_at) \
+     VALUES ($1, $2, now() + make_interval(secs => $3)) \
+     ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at, updated_at = now(), \
+     revision = CASE WHEN projections.expires_at <= now() THEN 1 ELSE projections.revision + 1 END";
+
+/// PostgreSQL-backed projection store (see `migrations/V006__projections.sql`,
+/// `migrations/V007__projection_checkpoints.sql` and `migrations/V008__projection_revisions_ttl.sql`)
+///
+/// Keys use the `"C"` collation so ordering and prefix scans are byte-wise,
+/// matching the in-memory adapter. Expiry is evaluated against the database clock.
+#[derive(Clone)]
+pub struct PostgresProjectionStore {
+    pool: PgPool,
+}
+
+impl PostgresProjectionStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl ProjectionStore for PostgresProjectionStore {
+    async fn save(&self, key: &str, data: &[u8]) -> InfraResult<()> {
+        sqlx::query(UPSERT)
+            .bind(key)
+            .bind(data)
+            .bind(None::<f64>)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn save_with_ttl(&self, key: &str, data: &[u8], ttl: Duration) -> InfraResult<()> {
+        sqlx::query(UPSERT)
+            .bind(key)
+            .bind(data)
+            .bind(ttl.as_secs_f64())
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> InfraResult<Option<Vec<u8>>> {
+        sqlx::query_scalar("SELECT value FROM projections WHERE key = $1 AND (expires_at IS NULL OR expires_at > now())")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn get_versioned(&self, key: &str) -> InfraResult<Option<VersionedEntry>> {
+        let row: Option<(Vec<u8>, i64, Option<DateTime<Utc>>)> = sqlx::query_as(
+            "SELECT value, revision, expires_at FROM projections \
+             WHERE key = $1 AND (expires_at IS NULL OR expires_at > now())",
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(row.map(|(value, revision, expires_at)| VersionedEntry { value, revision: revision as u64, expires_at }))
+    }
+
+    async fn delete(&self, key: &str) -> InfraResult<()> {
+        sqlx::query("DELETE FROM projections WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn compare_and_set(
+        &self,
+        key: &str,
+        expected: Option<ProjectionRevision>,
+        data: &[u8],
+        ttl: Option<Duration>,
+    ) -> InfraResult<ProjectionRevision> {
+        let ttl = ttl.map(|ttl| ttl.as_secs_f64());
+        let revision: Option<i64> = match expected {
+            // Insert, or take over an expired row
+            None => sqlx::query_scalar(
+                "INSERT INTO projections (key, value, expires_at) \
+                 VALUES ($1, $2, now() + make_interval(secs => $3)) \
+                 ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expires_at = EXCLUDED.expires_at, \
+                 revision = 1, updated_at = now() \
+                 WHERE projections.expires_at <= now() \
+                 RETURNING revision",
+            )
+            .bind(key)
+            .bind(data)
+            .bind(ttl)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?,
+            Some(expected) => sqlx::query_scalar(
+                "UPDATE projections SET value = $2, expires_at = now() + make_interval(secs => $3), \
+                 revision = revision + 1, updated_at = now() \
+                 WHERE key = $1 AND revision = $4 AND (expires_at IS NULL OR expires_at > now()) \
+                 RETURNING revision",
+            )
+            .bind(key)
+            .bind(data)
+            .bind(ttl)
+            .bind(expected as i64)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?,
+        };
+        revision.map(|r| r as u64).ok_or_else(|| revision_conflict(key, expected))
+    }
+
+    async fn compare_and_delete(&self, key: &str, expected: ProjectionRevision) -> InfraResult<()> {
+        let result = sqlx::query(
+            "DELETE FROM projections \
+             WHERE key = $1 AND revision = $2 AND (expires_at IS NULL OR expires_at > now())",
+        )
+        .bind(key)
+        .bind(expected as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        if result.rows_affected() == 0 {
+            return Err(revision_conflict(key, Some(expected)));
+        }
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> InfraResult<u64> {
+        let result = sqlx::query("DELETE FROM projections WHERE expires_at <= now()")
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected())
+    }
+
+    async fn scan_prefix(&self, prefix: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>> {
+        sqlx::query_as("SELECT key, value FROM projections WHERE key LIKE $1 ESCAPE '\\' \
+             AND (expires_at IS NULL OR expires_at > now()) ORDER BY key LIMIT $2")
+            .bind(format!("{}%", escape_like(prefix)))
+            .bind(limit.min(i64::MAX as usize) as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn scan_range(&self, start: &str, end: &str, limit: usize) -> InfraResult<Vec<ProjectionEntry>> {
+        sqlx::query_as("SELECT key, value FROM projections WHERE key >= $1 AND key < $2 \
+             AND (expires_at IS NULL OR expires_at > now()) ORDER BY key LIMIT $3")
+            .bind(start)
+            .bind(end)
+            .bind(limit.min(i64::MAX as usize) as i64)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn get_many(&self, keys: &[String]) -> InfraResult<Vec<Option<Vec<u8>>>> {
+        let rows: Vec<ProjectionEntry> = sqlx::query_as("SELECT key, value FROM projections \
+             WHERE key = ANY($1) AND (expires_at IS NULL OR expires_at > now())")
+            .bind(keys)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+        let mut found: HashMap<String, Vec<u8>> = rows.into_iter().collect();
+        Ok(keys.iter().map(|key| found.remove(key)).collect())
+    }
+
+    async fn save_many(&self, entries: Vec<ProjectionEntry>) -> InfraResult<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        // Last write wins for duplicate keys, as with sequential saves
+        let deduped: HashMap<String, Vec<u8>> = entries.into_iter().collect();
+        let (keys, values): (Vec<String>, Vec<Vec<u8>>) = deduped.into_iter().unzip();
+        sqlx::query(
+            "INSERT INTO projections (key, value) SELECT * FROM UNNEST($1::text[], $2::bytea[]) \
+             ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value, expires_at = NULL, updated_at = now(), \
+             revision = CASE WHEN projections.expires_at <= now() THEN 1 ELSE projections.revision + 1 END",
+        )
+        .bind(keys)
+        .bind(values)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for PostgresProjectionStore {
+    async fn load_checkpoint(&self, projection: &str) -> InfraResult<EventPosition> {
+        let position: Option<i64> = sqlx::query_scalar("SELECT position FROM projection_checkpoints WHERE projection = $1")
+            .bind(projection)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(position.unwrap_or(0) as u64)
+    }
+
+    async fn commit(&self, projection: &str, from: EventPosition, to: EventPosition, writes: Vec<ProjectionWrite>) -> InfraResult<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+
+        // Make sure the row exists, then lock it: a competing runner waits here and
+        // fails the check below once this transaction commits
+        sqlx::query("INSERT INTO projection_checkpoints (projection, position) VALUES ($1, 0) ON CONFLICT (projection) DO NOTHING")
+            .bind(projection)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        let current: i64 = sqlx::query_scalar("SELECT position FROM projection_checkpoints WHERE projection = $1 FOR UPDATE")
+            .bind(projection)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        let current = current as u64;
+        if current != from {
+            return Err(InfrastructureError::Concurrency(format!(
+                "projection {projection} checkpoint is at {current}, expected {from}"
+            )));
+        }
+
+        for write in writes {
+            match write {
+                ProjectionWrite::Save(key, data) => {
+                    sqlx::query(UPSERT)
+                        .bind(key)
+                        .bind(data)
+                        .bind(None::<f64>)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(db_error)?;
+                }
+                ProjectionWrite::Delete(key) => {
+                    sqlx::query("DELETE FROM projections WHERE key = $1")
+                        .bind(key)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(db_error)?;
+                }
+            }
+        }
+
+        sqlx::query("UPDATE projection_checkpoints SET position = $2, updated_at = now() WHERE projection = $1")
+            .bind(projection)
+            .bind(to as i64)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+
+        tx.commit().await.map_err(db_error)
+    }
+}
+
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn revision_conflict(key: &str, expected: Option<ProjectionRevision>) -> InfrastructureError {
+    InfrastructureError::Concurrency(format!("projection {key} is no longer at revision {expected:?}"))
+}
+
+fn db_error(e: sqlx::Error) -> InfrastructureError {
+    InfrastructureError::Database(e.to_string())
+}
@@ -0,0 +1,181 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use crate::application::ports::projection_store::{ProjectionEntry, ProjectionStore};
+use crate::infrastructure::messaging::kafka::common::{JsonDeserializer, JsonSerializer, MessageDeserializer, MessageSerializer};
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// Marks a value written by `TypedProjectionStore`; followed by the format version (u32, big endian)
+const FORMAT_TAG: &[u8; 3] = b"\xffPJ";
+const HEADER_LEN: usize = FORMAT_TAG.len() + 4;
+
+/// Stored projection as read by `TypedProjectionStore::get_entry`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedEntry<T> {
+    /// Written with the current format version
+    Current(T),
+    /// Written with another format version (0 = untagged raw bytes); `data` excludes the tag
+    Stale { version: u32, data: Vec<u8> },
+}
+
+/// Typed view over a `ProjectionStore`
+///
+/// Values are encoded with a `MessageSerializer` and prefixed with a format version, so
+/// entries written by an older read-model shape surface as `TypedEntry::Stale` (or
+/// `InfrastructureError::StaleFormat` from `get`) instead of an obscure decode error.
+/// Bump the version whenever `T` changes incompatibly.
+pub struct TypedProjectionStore<T, S: ?Sized = dyn ProjectionStore> {
+    store: Arc<S>,
+    version: u32,
+    serializer: Arc<dyn MessageSerializer<T>>,
+    deserializer: Arc<dyn MessageDeserializer<T>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T, S> TypedProjectionStore<T, S>
+where
+    T: Serialize + DeserializeOwned + 'static,
+    S: ProjectionStore + ?Sized,
+{
+    /// JSON-encoded values tagged with `version`
+    pub fn json(store: Arc<S>, version: u32) -> Self {
+        Self::with_codec(store, version, Arc::new(JsonSerializer), Arc::new(JsonDeserializer))
+    }
+}
+
+impl<T, S> Clone for TypedProjectionStore<T, S>
+where
+    S: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            version: self.version,
+            serializer: self.serializer.clone(),
+            deserializer: self.deserializer.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, S> TypedProjectionStore<T, S>
+where
+    S: ProjectionStore + ?Sized,
+{
+    pub fn with_codec(
+        store: Arc<S>,
+        version: u32,
+        serializer: Arc<dyn MessageSerializer<T>>,
+        deserializer: Arc<dyn MessageDeserializer<T>>,
+    ) -> Self {
+        Self { store, version, serializer, deserializer, _marker: PhantomData }
+    }
+
+    pub fn store(&self) -> &Arc<S> {
+        &self.store
+    }
+
+    /// Format version written by this store
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Serialize and tag a value, e.g. for `ProjectionContext::save`
+    pub fn encode(&self, value: &T) -> InfraResult<Vec<u8>> {
+        let body = self.serializer.serialize(value)?;
+        let mut data = Vec::with_capacity(HEADER_LEN + body.len());
+        data.extend_from_slice(FORMAT_TAG);
+        data.extend_from_slice(&self.version.to_be_bytes());
+        data.extend_from_slice(&body);
+        Ok(data)
+    }
+
+    /// Check the format tag and deserialize current entries
+    pub fn decode_entry(&self, data: Vec<u8>) -> InfraResult<TypedEntry<T>> {
+        match split_tag(&data) {
+            Some((version, body)) if version == self.version => Ok(TypedEntry::Current(self.deserializer.deserialize(body)?)),
+            Some((version, body)) => Ok(TypedEntry::Stale { version, data: body.to_vec() }),
+            None => Ok(TypedEntry::Stale { version: 0, data }),
+        }
+    }
+
+    /// Deserialize a tagged value, failing with `StaleFormat` for other versions
+    pub fn decode(&self, key: &str, data: Vec<u8>) -> InfraResult<T> {
+        match self.decode_entry(data)? {
+            TypedEntry::Current(value) => Ok(value),
+            TypedEntry::Stale { version, .. } => Err(InfrastructureError::StaleFormat(format!(
+                "projection {key} has format version {version}, expected {}",
+                self.version
+            ))),
+        }
+    }
+
+    pub async fn save(&self, key: &str, value: &T) -> InfraResult<()> {
+        self.store.save(key, &self.encode(value)?).await
+    }
+
+    pub async fn get(&self, key: &str) -> InfraResult<Option<T>> {
+        match self.store.get(key).await? {
+            Some(data) => self.decode(key, data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Like `get`, but returns stale entries instead of failing so they can be migrated
+    pub async fn get_entry(&self, key: &str) -> InfraResult<Option<TypedEntry<T>>> {
+        match self.store.get(key).await? {
+            Some(data) => self.decode_entry(data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn delete(&self, key: &str) -> InfraResult<()> {
+        self.store.delete(key).await
+    }
+
+    pub async fn get_many(&self, keys: &[String]) -> InfraResult<Vec<Option<T>>> {
+        let values = self.store.get_many(keys).await?;
+        keys.iter()
+            .zip(values)
+            .map(|(key, data)| data.map(|data| self.decode(key, data)).transpose())
+            .collect()
+    }
+
+    pub async fn save_many(&self, entries: Vec<(String, T)>) -> InfraResult<()> {
+        let encoded = entries.into_iter()
+            .map(|(key, value)| Ok((key, self.encode(&value)?)))
+            .collect::<InfraResult<Vec<ProjectionEntry>>>()?;
+        self.store.save_many(encoded).await
+    }
+
+    /// Up to `limit` decoded entries whose key starts with `prefix`, ordered by key
+    pub async fn scan_prefix(&self, prefix: &str, limit: usize) -> InfraResult<Vec<(String, T)>> {
+        let entries = self.store.scan_prefix(prefix, limit).await?;
+        self.decode_all(entries)
+    }
+
+    /// Up to `limit` decoded entries with `start <= key < end`, ordered by key
+    pub async fn scan_range(&self, start: &str, end: &str, limit: usize) -> InfraResult<Vec<(String, T)>> {
+        let entries = self.store.scan_range(start, end, limit).await?;
+        self.decode_all(entries)
+    }
+
+    fn decode_all(&self, entries: Vec<ProjectionEntry>) -> InfraResult<Vec<(String, T)>> {
+        entries.into_iter()
+            .map(|(key, data)| {
+                let value = self.decode(&key, data)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
+
+fn split_tag(data: &[u8]) -> Option<(u32, &[u8])> {
+    if data.len() < HEADER_LEN || !data.starts_with(FORMAT_TAG) {
+        return None;
+    }
+    let (header, body) = data.split_at(HEADER_LEN);
+    let version = u32::from_be_bytes(header[FORMAT_TAG.len()..].try_into().ok()?);
+    Some((version, body))
+}
@@ -1,6 +1,12 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
-use crate::shared::errors::InfraResult;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use crate::infrastructure::repositories::entity::{Entity, FieldValue};
+use crate::infrastructure::repositories::pg_entity::PgEntity;
+use crate::infrastructure::repositories::query::{
+    check_keyset_sort, effective_sort, Cursor, Operator, Page, PageRequest, Sort, SortOrder, Specification,
+};
+use crate::infrastructure::repositories::unit_of_work::{Connection, UnitOfWork};
+use crate::shared::errors::{InfraResult, InfrastructureError};
 
 /// Base repository trait for CRUD operations
 ///
@@ -14,38 +20,395 @@ pub trait Repository<T>: Send + Sync {
     /// Find all entities
     async fn find_all(&self) -> InfraResult<Vec<T>>;
 
-    /// Save a new entity
+    /// One page of the entities matching `spec`, ordered by `sort` and then by ID.
+    /// Keyset pages fail with `InfrastructureError::InvalidQuery` when sorting on a nullable field.
+    async fn find_page(&self, spec: &Specification, sort: &[Sort], page: &PageRequest) -> InfraResult<Page<T>>;
+
+    /// Count the entities matching `spec`
+    async fn count(&self, spec: &Specification) -> InfraResult<u64>;
+
+    /// Save a new entity, failing with `InfrastructureError::DuplicateKey` if its ID exists
     async fn save(&self, entity: &T) -> InfraResult<()>;
 
-    /// Update an existing entity
+    /// Update an existing entity, failing with `InfrastructureError::NotFound` if it does not exist.
+    /// Versioned entities must carry the stored version, otherwise this fails with
+    /// `InfrastructureError::Concurrency`; the stored version is then incremented, so reload
+    /// the entity (or bump its version) before updating it again.
     async fn update(&self, entity: &T) -> InfraResult<()>;
 
-    /// Delete an entity by its ID
+    /// Delete an entity by its ID, failing with `InfrastructureError::NotFound` if it does not exist.
+    /// Soft-deleted entities are hidden from every find but can be restored.
     async fn delete(&self, id: &str) -> InfraResult<()>;
+
+    /// Undo the soft delete of an entity, failing with `InfrastructureError::NotFound` if no
+    /// deleted entity has this ID and `InfrastructureError::InvalidQuery` without soft delete
+    async fn restore(&self, id: &str) -> InfraResult<()>;
 }
 
-/// Example base repository implementation using PostgreSQL
+/// Base repository implementation using PostgreSQL
+///
+/// Implements `Repository<T>` for every entity with a `PgEntity` mapping, e.g. via `pg_entity!`.
+/// Statements run on pooled connections unless the repository is enlisted in a `UnitOfWork`.
+#[derive(Clone)]
 pub struct PostgresRepository {
     pool: PgPool,
+    uow: Option<UnitOfWork>,
 }
 
 impl PostgresRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self { pool, uow: None }
     }
 
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
+
+    /// Repository whose statements run in `uow`'s transaction
+    pub fn in_unit_of_work(&self, uow: &UnitOfWork) -> Self {
+        Self { pool: self.pool.clone(), uow: Some(uow.clone()) }
+    }
+
+    async fn connection(&self) -> InfraResult<Connection<'_>> {
+        match &self.uow {
+            Some(uow) => uow.connection().await,
+            None => Connection::acquire(&self.pool).await,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: PgEntity> Repository<T> for PostgresRepository {
+    async fn find_by_id(&self, id: &str) -> InfraResult<Option<T>> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} = CAST($1 AS {}){}",
+            select_list::<T>(), T::TABLE, id_column::<T>(), T::ID_TYPE, and_live::<T>()
+        );
+        let mut conn = self.connection().await?;
+        sqlx::query_as(&sql)
+            .bind(id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn find_all(&self) -> InfraResult<Vec<T>> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE TRUE{} ORDER BY {}",
+            select_list::<T>(), T::TABLE, and_live::<T>(), id_column::<T>()
+        );
+        let mut conn = self.connection().await?;
+        sqlx::query_as(&sql)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn find_page(&self, spec: &Specification, sort: &[Sort], page: &PageRequest) -> InfraResult<Page<T>> {
+        let keys = effective_sort(sort, T::ID_FIELD);
+        if let PageRequest::Keyset { .. } = page {
+            check_keyset_sort::<T>(&keys)?;
+        }
+        let columns = keys
+            .iter()
+            .map(|key| Ok((check_field::<T>(&key.field)?, key.order)))
+            .collect::<InfraResult<Vec<_>>>()?;
+        let limit = page.limit().max(1);
+
+        let mut query = QueryBuilder::<Postgres>::new(format!("SELECT {} FROM {} WHERE ", select_list::<T>(), T::TABLE));
+        push_spec::<T>(&mut query, spec)?;
+        query.push(and_live::<T>());
+        if let PageRequest::Keyset { after: Some(cursor), .. } = page {
+            query.push(" AND ");
+            push_keyset(&mut query, &columns, cursor)?;
+        }
+        query.push(" ORDER BY ");
+        for (i, (column, order)) in columns.iter().enumerate() {
+            if i > 0 {
+                query.push(", ");
+            }
+            query.push(column);
+            query.push(if *order == SortOrder::Desc { " DESC" } else { " ASC" });
+        }
+        query.push(" LIMIT ");
+        query.push_bind(to_i64(limit + 1));
+        if let PageRequest::Offset { offset, .. } = page {
+            query.push(" OFFSET ");
+            query.push_bind(to_i64(*offset));
+        }
+
+        let mut conn = self.connection().await?;
+        let mut items: Vec<T> = query.build_query_as()
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        let has_more = items.len() as u64 > limit;
+        items.truncate(limit as usize);
+
+        let next = match page {
+            _ if !has_more => None,
+            PageRequest::Offset { offset, .. } => Some(PageRequest::offset(offset + limit, limit)),
+            PageRequest::Keyset { .. } => items.last().map(|last| {
+                let values = keys.iter().map(|key| last.field(&key.field).unwrap_or(FieldValue::Null)).collect();
+                PageRequest::after(Cursor(values), limit)
+            }),
+        };
+        Ok(Page { items, next })
+    }
+
+    async fn count(&self, spec: &Specification) -> InfraResult<u64> {
+        let mut query = QueryBuilder::<Postgres>::new(format!("SELECT COUNT(*) FROM {} WHERE ", T::TABLE));
+        push_spec::<T>(&mut query, spec)?;
+        query.push(and_live::<T>());
+        let mut conn = self.connection().await?;
+        let count: i64 = query.build_query_scalar()
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        Ok(count as u64)
+    }
+
+    async fn save(&self, entity: &T) -> InfraResult<()> {
+        let mut query = QueryBuilder::<Postgres>::new(format!("INSERT INTO {} ({}) VALUES (", T::TABLE, T::COLUMNS.join(", ")));
+        entity.bind_values(&mut query.separated(", "));
+        query.push(")");
+        let mut conn = self.connection().await?;
+        query.build()
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    InfrastructureError::DuplicateKey(format!("{} {}", T::TABLE, entity.id()))
+                }
+                e => db_error(e),
+            })?;
+        Ok(())
+    }
+
+    async fn update(&self, entity: &T) -> InfraResult<()> {
+        // Bound values are addressed through `v` so the version can be checked and bumped
+        let assignments: Vec<String> = T::FIELDS
+            .iter()
+            .map(|field| match T::VERSION_FIELD {
+                Some(version) if version == *field => format!("v.{field} + 1"),
+                _ => format!("v.{field}"),
+            })
+            .collect();
+        let mut query = QueryBuilder::<Postgres>::new(format!(
+            "UPDATE {} AS target SET ({}) = ROW({}) FROM (VALUES (",
+            T::TABLE, T::COLUMNS.join(", "), assignments.join(", ")
+        ));
+        entity.bind_values(&mut query.separated(", "));
+        query.push(format!(")) AS v({}) WHERE target.{} = CAST(", T::FIELDS.join(", "), id_column::<T>()));
+        query.push_bind(entity.id());
+        query.push(format!(" AS {})", T::ID_TYPE));
+        if let Some(version) = T::VERSION_FIELD {
+            query.push(format!(" AND target.{} = v.{version}", check_field::<T>(version)?));
+        }
+        if T::SOFT_DELETE {
+            query.push(" AND target.deleted_at IS NULL");
+        }
+
+        let mut conn = self.connection().await?;
+        let result = query.build()
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        let sql = format!("SELECT EXISTS (SELECT 1 FROM {} WHERE {} = CAST($1 AS {}){})", T::TABLE, id_column::<T>(), T::ID_TYPE, and_live::<T>());
+        let exists: bool = sqlx::query_scalar(&sql)
+            .bind(entity.id())
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        if exists && T::VERSION_FIELD.is_some() {
+            return Err(InfrastructureError::Concurrency(format!(
+                "{} {} is no longer at version {}", T::TABLE, entity.id(), entity.version()
+            )));
+        }
+        Err(InfrastructureError::NotFound(format!("{} {}", T::TABLE, entity.id())))
+    }
+
+    async fn delete(&self, id: &str) -> InfraResult<()> {
+        let sql = if T::SOFT_DELETE {
+            format!(
+                "UPDATE {} SET deleted_at = now() WHERE {} = CAST($1 AS {}) AND deleted_at IS NULL",
+                T::TABLE, id_column::<T>(), T::ID_TYPE
+            )
+        } else {
+            format!("DELETE FROM {} WHERE {} = CAST($1 AS {})", T::TABLE, id_column::<T>(), T::ID_TYPE)
+        };
+        let mut conn = self.connection().await?;
+        let result = sqlx::query(&sql)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        if result.rows_affected() == 0 {
+            return Err(InfrastructureError::NotFound(format!("{} {id}", T::TABLE)));
+        }
+        Ok(())
+    }
+
+    async fn restore(&self, id: &str) -> InfraResult<()> {
+        if !T::SOFT_DELETE {
+            return Err(InfrastructureError::InvalidQuery(format!("{} does not use soft delete", T::TABLE)));
+        }
+        let sql = format!(
+            "UPDATE {} SET deleted_at = NULL WHERE {} = CAST($1 AS {}) AND deleted_at IS NOT NULL",
+            T::TABLE, id_column::<T>(), T::ID_TYPE
+        );
+        let mut conn = self.connection().await?;
+        let result = sqlx::query(&sql)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        if result.rows_affected() == 0 {
+            return Err(InfrastructureError::NotFound(format!("deleted {} {id}", T::TABLE)));
+        }
+        Ok(())
+    }
 }
 
-// Implement Repository trait for your specific entities
-// Example:
-// #[async_trait]
-// impl Repository<YourEntity> for PostgresRepository {
-//     async fn find_by_id(&self, id: &str) -> InfraResult<Option<YourEntity>> {
-//         // Your implementation here
-//     }
-//     // ... other methods
-// }
+/// Excludes soft-deleted rows; appended to a WHERE clause
+fn and_live<T: Entity>() -> &'static str {
+    if T::SOFT_DELETE { " AND deleted_at IS NULL" } else { "" }
+}
+
+/// Column names end up in SQL, so only the entity's own fields are accepted; returns the field's column
+fn check_field<T: PgEntity>(field: &str) -> InfraResult<&'static str> {
+    T::column(field).ok_or_else(|| InfrastructureError::InvalidQuery(format!("unknown field {field}")))
+}
+
+fn id_column<T: PgEntity>() -> &'static str {
+    T::column(T::ID_FIELD).unwrap_or(T::ID_FIELD)
+}
+
+/// Every column, aliased to its field name where they differ so `FromRow` finds it
+fn select_list<T: PgEntity>() -> String {
+    T::FIELDS
+        .iter()
+        .zip(T::COLUMNS)
+        .map(|(field, column)| if field == column { column.to_string() } else { format!("{column} AS {field}") })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
 
+fn push_spec<T: PgEntity>(query: &mut QueryBuilder<'_, Postgres>, spec: &Specification) -> InfraResult<()> {
+    match spec {
+        Specification::All => {
+            query.push("TRUE");
+        }
+        Specification::Field { field, op, value } => {
+            query.push(check_field::<T>(field)?);
+            query.push(match op {
+                Operator::Eq => " = ",
+                Operator::Ne => " <> ",
+                Operator::Lt => " < ",
+                Operator::Lte => " <= ",
+                Operator::Gt => " > ",
+                Operator::Gte => " >= ",
+                Operator::Like => " LIKE ",
+            });
+            push_value(query, value);
+        }
+        Specification::In { field, values } => {
+            let column = check_field::<T>(field)?;
+            if values.is_empty() {
+                query.push("FALSE");
+                return Ok(());
+            }
+            query.push(column);
+            query.push(" IN (");
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    query.push(", ");
+                }
+                push_value(query, value);
+            }
+            query.push(")");
+        }
+        Specification::IsNull(field) => {
+            query.push(check_field::<T>(field)?);
+            query.push(" IS NULL");
+        }
+        Specification::And(specs) | Specification::Or(specs) => {
+            let (empty, separator) = match spec {
+                Specification::And(_) => ("TRUE", " AND "),
+                _ => ("FALSE", " OR "),
+            };
+            if specs.is_empty() {
+                query.push(empty);
+                return Ok(());
+            }
+            query.push("(");
+            for (i, spec) in specs.iter().enumerate() {
+                if i > 0 {
+                    query.push(separator);
+                }
+                push_spec::<T>(query, spec)?;
+            }
+            query.push(")");
+        }
+        Specification::Not(spec) => {
+            query.push("NOT (");
+            push_spec::<T>(query, spec)?;
+            query.push(")");
+        }
+    }
+    Ok(())
+}
+
+/// Rows after `cursor` in `keys` (column, order) order, expanded to
+/// `(k0 > v0) OR (k0 = v0 AND k1 > v1) OR ...` so sort directions may differ per key;
+/// `check_keyset_sort` has ruled out nullable keys
+fn push_keyset(query: &mut QueryBuilder<'_, Postgres>, keys: &[(&str, SortOrder)], cursor: &Cursor) -> InfraResult<()> {
+    if cursor.0.len() != keys.len() {
+        return Err(InfrastructureError::InvalidQuery("cursor does not match the sort order".to_string()));
+    }
+    query.push("(");
+    for i in 0..keys.len() {
+        if i > 0 {
+            query.push(" OR ");
+        }
+        query.push("(");
+        for ((column, _), value) in keys[..i].iter().zip(&cursor.0) {
+            query.push(column);
+            query.push(" = ");
+            push_value(query, value);
+            query.push(" AND ");
+        }
+        let (column, order) = keys[i];
+        query.push(column);
+        query.push(if order == SortOrder::Desc { " < " } else { " > " });
+        push_value(query, &cursor.0[i]);
+        query.push(")");
+    }
+    query.push(")");
+    Ok(())
+}
+
+fn push_value(query: &mut QueryBuilder<'_, Postgres>, value: &FieldValue) {
+    match value.clone() {
+        FieldValue::Null => query.push("NULL"),
+        FieldValue::Bool(v) => query.push_bind(v),
+        FieldValue::Int(v) => query.push_bind(v),
+        FieldValue::Float(v) => query.push_bind(v),
+        FieldValue::Text(v) => query.push_bind(v),
+        FieldValue::Uuid(v) => query.push_bind(v),
+        FieldValue::Timestamp(v) => query.push_bind(v),
+        FieldValue::Json(v) => query.push_bind(v),
+    };
+}
+
+fn to_i64(value: u64) -> i64 {
+    value.min(i64::MAX as u64) as i64
+}
+
+fn db_error(e: sqlx::Error) -> InfrastructureError {
+    InfrastructureError::Database(e.to_string())
+}
@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Field value used by specifications, sorting and pagination cursors
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FieldValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Uuid(Uuid),
+    Timestamp(DateTime<Utc>),
+    Json(serde_json::Value),
+}
+
+impl FieldValue {
+    /// SQL-style comparison: `None` when either side is null or the types are not comparable.
+    /// Integers and floats compare with each other.
+    pub fn compare(&self, other: &FieldValue) -> Option<Ordering> {
+        match (self, other) {
+            (FieldValue::Bool(a), FieldValue::Bool(b)) => a.partial_cmp(b),
+            (FieldValue::Int(a), FieldValue::Int(b)) => a.partial_cmp(b),
+            (FieldValue::Int(a), FieldValue::Float(b)) => (*a as f64).partial_cmp(b),
+            (FieldValue::Float(a), FieldValue::Int(b)) => a.partial_cmp(&(*b as f64)),
+            (FieldValue::Float(a), FieldValue::Float(b)) => a.partial_cmp(b),
+            (FieldValue::Text(a), FieldValue::Text(b)) => a.partial_cmp(b),
+            (FieldValue::Uuid(a), FieldValue::Uuid(b)) => a.partial_cmp(b),
+            (FieldValue::Timestamp(a), FieldValue::Timestamp(b)) => a.partial_cmp(b),
+            (FieldValue::Json(a), FieldValue::Json(b)) if a == b => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, FieldValue::Null)
+    }
+}
+
+macro_rules! field_value_from {
+    ($($source:ty => $variant:ident $(as $target:ty)?),* $(,)?) => {
+        $(impl From<$source> for FieldValue {
+            fn from(value: $source) -> Self {
+                FieldValue::$variant(value $(as $target)?)
+            }
+        })*
+    };
+}
+
+field_value_from! {
+    bool => Bool,
+    i16 => Int as i64,
+    i32 => Int as i64,
+    i64 => Int,
+    f32 => Float as f64,
+    f64 => Float,
+    String => Text,
+    Uuid => Uuid,
+    DateTime<Utc> => Timestamp,
+    serde_json::Value => Json,
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::Text(value.to_string())
+    }
+}
+
+impl<T: Into<FieldValue>> From<Option<T>> for FieldValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(FieldValue::Null, Into::into)
+    }
+}
+
+/// Entity with a string-addressable primary key and named fields
+///
+/// Field names double as column names for the Postgres repository. Implement it with
+/// the `entity!` macro, or `pg_entity!` for entities stored in Postgres.
+pub trait Entity: Send + Sync + 'static {
+    /// Field holding the primary key
+    const ID_FIELD: &'static str;
+
+    /// Every persisted field, including the id
+    const FIELDS: &'static [&'static str];
+
+    /// Integer field used for optimistic locking: updates must carry the stored
+    /// version and increment it
+    const VERSION_FIELD: Option<&'static str> = None;
+
+    /// Fields that may hold NULL; keyset pages refuse to sort on them
+    const NULLABLE: &'static [&'static str] = &[];
+
+    /// Whether `delete` only marks the entity deleted so it can be restored.
+    /// Postgres tables then need a nullable `deleted_at TIMESTAMPTZ` column.
+    const SOFT_DELETE: bool = false;
+
+    /// Primary key of this entity, as accepted by `Repository::find_by_id`
+    fn id(&self) -> String;
+
+    /// Value of a field listed in `FIELDS`
+    fn field(&self, name: &str) -> Option<FieldValue>;
+
+    /// Current optimistic-locking version (0 when unversioned)
+    fn version(&self) -> i64 {
+        match Self::VERSION_FIELD.and_then(|field| self.field(field)) {
+            Some(FieldValue::Int(version)) => version,
+            _ => 0,
+        }
+    }
+
+    /// Overwrite the version field; a no-op for unversioned entities
+    fn set_version(&mut self, _version: i64) {}
+}
+
+/// Implement `Entity` from a field list
+///
+/// ```ignore
+/// entity!(Customer, id = id, fields = [name, email]);
+/// entity!(Order, id = id, fields = [total, note], nullable = [note], version = version, soft_delete = true);
+/// ```
+///
+/// Every field must be `Clone` and convertible into `FieldValue`. Fields of `Option` type
+/// belong in `nullable`. The version field is appended to the fields and must be an integer.
+#[macro_export]
+macro_rules! entity {
+    (
+        $entity:ty, id = $id:ident, fields = [$($field:ident),* $(,)?]
+        $(, nullable = [$($nullable:ident),* $(,)?])? $(, version = $version:ident)? $(, soft_delete = $soft_delete:literal)? $(,)?
+    ) => {
+        impl $crate::infrastructure::repositories::Entity for $entity {
+            const ID_FIELD: &'static str = stringify!($id);
+            const FIELDS: &'static [&'static str] = &[stringify!($id), $(stringify!($field),)* $(stringify!($version))?];
+            const VERSION_FIELD: Option<&'static str> = { let field: Option<&'static str> = None; $(let field = Some(stringify!($version));)? field };
+            const NULLABLE: &'static [&'static str] = &[$($(stringify!($nullable)),*)?];
+            const SOFT_DELETE: bool = { let soft_delete = false; $(let soft_delete = $soft_delete;)? soft_delete };
+
+            fn id(&self) -> String {
+                self.$id.to_string()
+            }
+
+            fn field(&self, name: &str) -> Option<$crate::infrastructure::repositories::FieldValue> {
+                match name {
+                    stringify!($id) => Some(self.$id.clone().into()),
+                    $(stringify!($field) => Some(self.$field.clone().into()),)*
+                    $(stringify!($version) => Some(self.$version.clone().into()),)?
+                    _ => None,
+                }
+            }
+
+            $(fn set_version(&mut self, version: i64) {
+                self.$version = version as _;
+            })?
+        }
+    };
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+use crate::application::ports::event_store::EventStore;
+use crate::application::services::AggregateLoader;
+use crate::domain::repositories::AggregateRepository;
+use crate::infrastructure::event_store::EventRegistry;
+use crate::shared::errors::{AppResult, ApplicationError, DomainError, DomainResult, InfrastructureError};
+use crate::shared::traits::AggregateRoot;
+use crate::shared::types::{EventMetadata, ExpectedVersion, LoadedAggregate};
+
+/// Generic repository for any event-sourced aggregate
+///
+/// Loads by replaying the aggregate's stream (from its latest snapshot when the loader
+/// has a snapshot store) and saves by appending the uncommitted events at the version
+/// the aggregate was loaded at.
+pub struct EventSourcedRepository<A: AggregateRoot> {
+    loader: AggregateLoader,
+    registry: Arc<EventRegistry<A::Event>>,
+}
+
+impl<A: AggregateRoot> EventSourcedRepository<A> {
+    pub fn new(events: Arc<dyn EventStore>, registry: EventRegistry<A::Event>) -> Self {
+        Self::with_loader(AggregateLoader::new(events), registry)
+    }
+
+    /// Use a preconfigured loader, e.g. one with a snapshot store attached
+    pub fn with_loader(loader: AggregateLoader, registry: EventRegistry<A::Event>) -> Self {
+        Self { loader, registry: Arc::new(registry) }
+    }
+}
+
+#[async_trait]
+impl<A> AggregateRepository<A> for EventSourcedRepository<A>
+where
+    A: AggregateRoot + Default + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    async fn load(&self, id: Uuid) -> DomainResult<Option<LoadedAggregate<A>>> {
+        let registry = self.registry.clone();
+        self.loader
+            .load(id, move |envelope| registry.decode(&envelope.event_type, envelope.payload.clone()))
+            .await
+            .map_err(domain_error)
+    }
+
+    async fn save(&self, aggregate: &mut LoadedAggregate<A>, metadata: EventMetadata) -> DomainResult<()> {
+        // Encode before draining so a failed append leaves the uncommitted events in place
+        let events = aggregate
+            .aggregate
+            .uncommitted_events()
+            .iter()
+            .map(|e| self.registry.encode(e))
+            .collect::<AppResult<Vec<_>>>()
+            .map_err(domain_error)?;
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let expected = match aggregate.version {
+            0 => ExpectedVersion::NoStream,
+            v => ExpectedVersion::Exact(v),
+        };
+        let appended = self
+            .loader
+            .event_store()
+            .append(aggregate.aggregate_id, expected, events, metadata)
+            .await
+            .map_err(|e| domain_error(e.into()))?;
+
+        aggregate.aggregate.take_uncommitted();
+        if let Some(last) = appended.last() {
+            aggregate.version = last.sequence;
+        }
+
+        // Events are committed at this point; a failed snapshot only costs replay time later
+        match self
+            .loader
+            .snapshot_if_due(aggregate.aggregate_id, &aggregate.aggregate, aggregate.snapshot_version, aggregate.version)
+            .await
+        {
+            Ok(true) => aggregate.snapshot_version = aggregate.version,
+            Ok(false) => {}
+            Err(e) => warn!(aggregate_id = %aggregate.aggregate_id, error = %e, "Failed to save snapshot"),
+        }
+
+        Ok(())
+    }
+}
+
+/// Map loader and event store failures onto the errors the domain contract promises
+fn domain_error(e: ApplicationError) -> DomainError {
+    match e {
+        ApplicationError::Domain(e) => e,
+        ApplicationError::Infrastructure(InfrastructureError::Concurrency(msg)) => DomainError::Concurrency(msg),
+        ApplicationError::Infrastructure(InfrastructureError::StreamDeleted(msg)) => DomainError::StreamDeleted(msg),
+        other => DomainError::Persistence(other.to_string()),
+    }
+}
@@ -0,0 +1,239 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use crate::infrastructure::repositories::base_repository::Repository;
+use crate::infrastructure::repositories::entity::{Entity, FieldValue};
+use crate::infrastructure::repositories::query::{
+    check_keyset_sort, effective_sort, Cursor, Page, PageRequest, Sort, SortOrder, Specification,
+};
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// In-memory `Repository<T>` for unit tests and local runs; clones share storage
+///
+/// Mirrors `PostgresRepository`: duplicate saves fail with `DuplicateKey`, updates and
+/// deletes of missing entities with `NotFound`, stale versions with `Concurrency`, and
+/// specifications, sorting and paging follow SQL semantics (nulls never match comparisons
+/// and sort last ascending). Text is compared byte-wise rather than by database collation.
+pub struct InMemoryRepository<T> {
+    entities: Arc<RwLock<BTreeMap<String, Record<T>>>>,
+}
+
+struct Record<T> {
+    entity: T,
+    /// Soft-deleted: kept for `restore` and still occupying its id
+    deleted: bool,
+}
+
+impl<T> Clone for InMemoryRepository<T> {
+    fn clone(&self) -> Self {
+        Self { entities: self.entities.clone() }
+    }
+}
+
+impl<T> Default for InMemoryRepository<T> {
+    fn default() -> Self {
+        Self { entities: Arc::new(RwLock::new(BTreeMap::new())) }
+    }
+}
+
+impl<T: Entity + Clone> InMemoryRepository<T> {
+    pub fn new() -> Self { Self::default() }
+
+    /// Repository pre-populated with `entities`, later ones replacing earlier ones with the same id
+    pub fn with_entities(entities: impl IntoIterator<Item = T>) -> Self {
+        let entities = entities
+            .into_iter()
+            .map(|entity| (entity.id(), Record { entity, deleted: false }))
+            .collect();
+        Self { entities: Arc::new(RwLock::new(entities)) }
+    }
+
+    /// Number of stored entities, including soft-deleted ones
+    pub async fn len(&self) -> usize {
+        self.entities.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entities.read().await.is_empty()
+    }
+
+    fn live(records: &BTreeMap<String, Record<T>>) -> impl Iterator<Item = &T> {
+        records.values().filter(|record| !record.deleted).map(|record| &record.entity)
+    }
+
+    pub async fn clear(&self) {
+        self.entities.write().await.clear();
+    }
+
+    /// Entities matching `spec`, sorted by `keys`
+    async fn select(&self, spec: &Specification, keys: &[Sort]) -> InfraResult<Vec<T>> {
+        check_fields::<T>(spec.fields().into_iter().chain(keys.iter().map(|key| key.field.as_str())))?;
+        let guard = self.entities.read().await;
+        let mut selected = Vec::new();
+        for entity in Self::live(&guard) {
+            if spec.matches(entity)? {
+                selected.push(entity.clone());
+            }
+        }
+        selected.sort_by(|a, b| compare_entities(a, b, keys));
+        Ok(selected)
+    }
+}
+
+#[async_trait]
+impl<T: Entity + Clone> Repository<T> for InMemoryRepository<T> {
+    async fn find_by_id(&self, id: &str) -> InfraResult<Option<T>> {
+        Ok(self.entities.read().await.get(id).filter(|record| !record.deleted).map(|record| record.entity.clone()))
+    }
+
+    async fn find_all(&self) -> InfraResult<Vec<T>> {
+        self.select(&Specification::All, &effective_sort(&[], T::ID_FIELD)).await
+    }
+
+    async fn find_page(&self, spec: &Specification, sort: &[Sort], page: &PageRequest) -> InfraResult<Page<T>> {
+        let keys = effective_sort(sort, T::ID_FIELD);
+        if let PageRequest::Keyset { .. } = page {
+            check_keyset_sort::<T>(&keys)?;
+        }
+        let limit = page.limit().max(1);
+        let selected = self.select(spec, &keys).await?;
+
+        let mut items: Vec<T> = match page {
+            PageRequest::Offset { offset, .. } => {
+                selected.into_iter().skip(*offset as usize).take(limit as usize + 1).collect()
+            }
+            PageRequest::Keyset { after, .. } => {
+                if let Some(cursor) = after {
+                    if cursor.0.len() != keys.len() {
+                        return Err(InfrastructureError::InvalidQuery("cursor does not match the sort order".to_string()));
+                    }
+                }
+                selected
+                    .into_iter()
+                    .filter(|entity| after.as_ref().is_none_or(|cursor| is_after(entity, &keys, cursor)))
+                    .take(limit as usize + 1)
+                    .collect()
+            }
+        };
+        let has_more = items.len() as u64 > limit;
+        items.truncate(limit as usize);
+
+        let next = match page {
+            _ if !has_more => None,
+            PageRequest::Offset { offset, .. } => Some(PageRequest::offset(offset + limit, limit)),
+            PageRequest::Keyset { .. } => items.last().map(|last| {
+                let values = keys.iter().map(|key| last.field(&key.field).unwrap_or(FieldValue::Null)).collect();
+                PageRequest::after(Cursor(values), limit)
+            }),
+        };
+        Ok(Page { items, next })
+    }
+
+    async fn count(&self, spec: &Specification) -> InfraResult<u64> {
+        check_fields::<T>(spec.fields())?;
+        let mut count = 0;
+        for entity in Self::live(&*self.entities.read().await) {
+            if spec.matches(entity)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    async fn save(&self, entity: &T) -> InfraResult<()> {
+        let mut guard = self.entities.write().await;
+        let id = entity.id();
+        if guard.contains_key(&id) {
+            return Err(InfrastructureError::DuplicateKey(id));
+        }
+        guard.insert(id, Record { entity: entity.clone(), deleted: false });
+        Ok(())
+    }
+
+    async fn update(&self, entity: &T) -> InfraResult<()> {
+        let mut guard = self.entities.write().await;
+        let record = guard
+            .get_mut(&entity.id())
+            .filter(|record| !record.deleted)
+            .ok_or_else(|| InfrastructureError::NotFound(entity.id()))?;
+        let mut updated = entity.clone();
+        if T::VERSION_FIELD.is_some() {
+            let version = record.entity.version();
+            if entity.version() != version {
+                return Err(InfrastructureError::Concurrency(format!(
+                    "{} is at version {version}, not {}", entity.id(), entity.version()
+                )));
+            }
+            updated.set_version(version + 1);
+        }
+        record.entity = updated;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> InfraResult<()> {
+        let mut guard = self.entities.write().await;
+        match guard.get_mut(id) {
+            Some(record) if T::SOFT_DELETE && !record.deleted => {
+                record.deleted = true;
+                Ok(())
+            }
+            Some(_) if !T::SOFT_DELETE => {
+                guard.remove(id);
+                Ok(())
+            }
+            _ => Err(InfrastructureError::NotFound(id.to_string())),
+        }
+    }
+
+    async fn restore(&self, id: &str) -> InfraResult<()> {
+        if !T::SOFT_DELETE {
+            return Err(InfrastructureError::InvalidQuery(format!("{id}: entity does not use soft delete")));
+        }
+        match self.entities.write().await.get_mut(id) {
+            Some(record) if record.deleted => {
+                record.deleted = false;
+                Ok(())
+            }
+            _ => Err(InfrastructureError::NotFound(format!("deleted {id}"))),
+        }
+    }
+}
+
+/// Postgres default ordering: nulls sort last ascending and first descending
+fn compare_entities<T: Entity>(a: &T, b: &T, keys: &[Sort]) -> Ordering {
+    for key in keys {
+        let (a, b) = (a.field(&key.field).unwrap_or(FieldValue::Null), b.field(&key.field).unwrap_or(FieldValue::Null));
+        let ordering = match (a.is_null(), b.is_null()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => a.compare(&b).unwrap_or(Ordering::Equal),
+        };
+        let ordering = if key.order == SortOrder::Desc { ordering.reverse() } else { ordering };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Same condition as the Postgres keyset predicate, so null keys never qualify
+fn is_after<T: Entity>(entity: &T, keys: &[Sort], cursor: &Cursor) -> bool {
+    let values: Vec<FieldValue> = keys.iter().map(|key| entity.field(&key.field).unwrap_or(FieldValue::Null)).collect();
+    (0..keys.len()).any(|i| {
+        let prefix_equal = (0..i).all(|j| values[j].compare(&cursor.0[j]) == Some(Ordering::Equal));
+        let beyond = if keys[i].order == SortOrder::Desc { Ordering::Less } else { Ordering::Greater };
+        prefix_equal && values[i].compare(&cursor.0[i]) == Some(beyond)
+    })
+}
+
+/// Reject fields the entity does not declare, like the `PostgresRepository` whitelist,
+/// whether or not any entity is stored
+fn check_fields<'a, T: Entity>(fields: impl IntoIterator<Item = &'a str>) -> InfraResult<()> {
+    match fields.into_iter().find(|field| !T::FIELDS.contains(field)) {
+        Some(field) => Err(InfrastructureError::InvalidQuery(format!("unknown field {field}"))),
+        None => Ok(()),
+    }
+}
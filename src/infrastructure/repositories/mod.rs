@@ -1,3 +1,15 @@
 pub mod base_repository;
+pub mod entity;
+pub mod event_sourced_repository;
+pub mod in_memory_repository;
+pub mod pg_entity;
+pub mod query;
+pub mod unit_of_work;
 
 pub use base_repository::{Repository, PostgresRepository};
+pub use entity::{Entity, FieldValue};
+pub use event_sourced_repository::EventSourcedRepository;
+pub use in_memory_repository::InMemoryRepository;
+pub use pg_entity::{ColumnValues, PgEntity};
+pub use query::{Cursor, Operator, Page, PageRequest, Sort, SortOrder, Specification};
+pub use unit_of_work::{Connection, UnitOfWork};
@@ -0,0 +1,78 @@
+use sqlx::postgres::PgRow;
+use sqlx::query_builder::Separated;
+use sqlx::{FromRow, Postgres};
+use crate::infrastructure::repositories::entity::Entity;
+
+/// Bind list handed to `PgEntity::bind_values`
+pub type ColumnValues<'qb, 'args> = Separated<'qb, 'args, Postgres, &'static str>;
+
+/// Table mapping that gives an entity a working `Repository<T>` on `PostgresRepository`
+///
+/// `COLUMNS` names the column of each entry of `Entity::FIELDS`. Rows are selected with
+/// columns aliased to field names and read with the entity's `FromRow` implementation
+/// (usually `#[derive(sqlx::FromRow)]`); they are written by binding the fields in order.
+/// Prefer the `pg_entity!` macro over a manual impl.
+pub trait PgEntity: Entity + for<'r> FromRow<'r, PgRow> + Unpin {
+    /// Table name, optionally schema-qualified
+    const TABLE: &'static str;
+
+    /// SQL type the string ids passed to the repository are cast to, e.g. `uuid` or `bigint`
+    const ID_TYPE: &'static str = "text";
+
+    /// Column of each entry of `Entity::FIELDS`, in the same order; defaults to the field names
+    const COLUMNS: &'static [&'static str] = Self::FIELDS;
+
+    /// Bind one value per entry of `Entity::FIELDS`, in the same order
+    fn bind_values(&self, values: &mut ColumnValues<'_, '_>);
+
+    /// Column mapped to `field`, or `None` if the entity has no such field
+    fn column(field: &str) -> Option<&'static str> {
+        let index = Self::FIELDS.iter().position(|f| *f == field)?;
+        Self::COLUMNS.get(index).copied()
+    }
+}
+
+/// Implement `Entity` and `PgEntity` from a table declaration
+///
+/// ```ignore
+/// #[derive(Clone, sqlx::FromRow)]
+/// struct Customer { id: Uuid, name: String, email: String, version: i64 }
+///
+/// pg_entity!(Customer, table = "customers", id = id: "uuid", columns = [name, email]);
+/// pg_entity!(Customer, table = "customers", id = id => "customer_id": "uuid",
+///            columns = [name => "full_name", email], nullable = [email], version = version => "row_version",
+///            soft_delete = true);
+/// ```
+///
+/// A field is stored in the column of the same name unless mapped with `field => "column"`.
+/// Every field must be `Clone`, encodable by sqlx and convertible into `FieldValue`; fields
+/// of nullable columns belong in `nullable`. The id type defaults to `text`.
+#[macro_export]
+macro_rules! pg_entity {
+    (
+        $entity:ty, table = $table:literal, id = $id:ident $(=> $id_column:literal)? $(: $id_type:literal)?,
+        columns = [$($column:ident $(=> $column_name:literal)?),* $(,)?]
+        $(, nullable = [$($nullable:ident),* $(,)?])? $(, version = $version:ident $(=> $version_column:literal)?)? $(, soft_delete = $soft_delete:literal)? $(,)?
+    ) => {
+        $crate::entity!(
+            $entity, id = $id, fields = [$($column),*]
+            $(, nullable = [$($nullable),*])? $(, version = $version)? $(, soft_delete = $soft_delete)?
+        );
+
+        impl $crate::infrastructure::repositories::PgEntity for $entity {
+            const TABLE: &'static str = $table;
+            const ID_TYPE: &'static str = { let id_type = "text"; $(let id_type = $id_type;)? id_type };
+            const COLUMNS: &'static [&'static str] = &[
+                { let column = stringify!($id); $(let column = $id_column;)? column },
+                $({ let column = stringify!($column); $(let column = $column_name;)? column },)*
+                $({ let column = stringify!($version); $(let column = $version_column;)? column })?
+            ];
+
+            fn bind_values(&self, values: &mut $crate::infrastructure::repositories::ColumnValues<'_, '_>) {
+                values.push_bind(self.$id.clone());
+                $(values.push_bind(self.$column.clone());)*
+                $(values.push_bind(self.$version.clone());)?
+            }
+        }
+    };
+}
@@ -0,0 +1,314 @@
+use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+use crate::infrastructure::repositories::entity::{Entity, FieldValue};
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+/// Comparison applied by a field predicate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    /// SQL `LIKE` pattern with `%` and `_` wildcards; `\` matches the next character literally
+    Like,
+}
+
+/// Filter over entity fields, combinable with AND/OR/NOT
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Specification {
+    /// Matches every entity
+    #[default]
+    All,
+    Field { field: String, op: Operator, value: FieldValue },
+    In { field: String, values: Vec<FieldValue> },
+    IsNull(String),
+    And(Vec<Specification>),
+    Or(Vec<Specification>),
+    Not(Box<Specification>),
+}
+
+impl Specification {
+    pub fn field(field: &str, op: Operator, value: impl Into<FieldValue>) -> Self {
+        Specification::Field { field: field.to_string(), op, value: value.into() }
+    }
+
+    pub fn eq(field: &str, value: impl Into<FieldValue>) -> Self { Self::field(field, Operator::Eq, value) }
+    pub fn ne(field: &str, value: impl Into<FieldValue>) -> Self { Self::field(field, Operator::Ne, value) }
+    pub fn lt(field: &str, value: impl Into<FieldValue>) -> Self { Self::field(field, Operator::Lt, value) }
+    pub fn lte(field: &str, value: impl Into<FieldValue>) -> Self { Self::field(field, Operator::Lte, value) }
+    pub fn gt(field: &str, value: impl Into<FieldValue>) -> Self { Self::field(field, Operator::Gt, value) }
+    pub fn gte(field: &str, value: impl Into<FieldValue>) -> Self { Self::field(field, Operator::Gte, value) }
+    pub fn like(field: &str, pattern: &str) -> Self { Self::field(field, Operator::Like, pattern) }
+
+    pub fn is_in<V: Into<FieldValue>>(field: &str, values: impl IntoIterator<Item = V>) -> Self {
+        Specification::In { field: field.to_string(), values: values.into_iter().map(Into::into).collect() }
+    }
+
+    pub fn is_null(field: &str) -> Self {
+        Specification::IsNull(field.to_string())
+    }
+
+    pub fn and(self, other: Specification) -> Self {
+        match self {
+            Specification::All => other,
+            Specification::And(mut specs) => { specs.push(other); Specification::And(specs) }
+            spec => Specification::And(vec![spec, other]),
+        }
+    }
+
+    pub fn or(self, other: Specification) -> Self {
+        match self {
+            Specification::Or(mut specs) => { specs.push(other); Specification::Or(specs) }
+            spec => Specification::Or(vec![spec, other]),
+        }
+    }
+
+    pub fn negate(self) -> Self {
+        Specification::Not(Box::new(self))
+    }
+
+    /// Whether `entity` satisfies this specification, with the same null handling as SQL
+    pub fn matches<T: Entity>(&self, entity: &T) -> InfraResult<bool> {
+        Ok(self.evaluate(entity)? == Some(true))
+    }
+
+    /// Three-valued evaluation: `None` is SQL's unknown, e.g. a comparison with null
+    fn evaluate<T: Entity>(&self, entity: &T) -> InfraResult<Option<bool>> {
+        Ok(match self {
+            Specification::All => Some(true),
+            Specification::Field { field, op, value } => {
+                let actual = field_value(entity, field)?;
+                match op {
+                    Operator::Like => match (&actual, value) {
+                        (FieldValue::Text(text), FieldValue::Text(pattern)) => Some(like(text, pattern)?),
+                        _ => None,
+                    },
+                    op => actual.compare(value).map(|ordering| match op {
+                        Operator::Eq => ordering == Ordering::Equal,
+                        Operator::Ne => ordering != Ordering::Equal,
+                        Operator::Lt => ordering == Ordering::Less,
+                        Operator::Lte => ordering != Ordering::Greater,
+                        Operator::Gt => ordering == Ordering::Greater,
+                        _ => ordering != Ordering::Less,
+                    }),
+                }
+            }
+            Specification::In { field, values } => {
+                let actual = field_value(entity, field)?;
+                let results: Vec<Option<bool>> = values
+                    .iter()
+                    .map(|value| actual.compare(value).map(|ordering| ordering == Ordering::Equal))
+                    .collect();
+                any(results)
+            }
+            Specification::IsNull(field) => Some(field_value(entity, field)?.is_null()),
+            Specification::And(specs) => {
+                let results = specs.iter().map(|spec| spec.evaluate(entity)).collect::<InfraResult<Vec<_>>>()?;
+                if results.contains(&Some(false)) {
+                    Some(false)
+                } else if results.contains(&None) {
+                    None
+                } else {
+                    Some(true)
+                }
+            }
+            Specification::Or(specs) => {
+                any(specs.iter().map(|spec| spec.evaluate(entity)).collect::<InfraResult<Vec<_>>>()?)
+            }
+            Specification::Not(spec) => spec.evaluate(entity)?.map(|result| !result),
+        })
+    }
+
+    /// Every field name referenced by this specification
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            Specification::All => Vec::new(),
+            Specification::Field { field, .. } | Specification::In { field, .. } | Specification::IsNull(field) => vec![field],
+            Specification::And(specs) | Specification::Or(specs) => specs.iter().flat_map(Specification::fields).collect(),
+            Specification::Not(spec) => spec.fields(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Sort key; results are always ordered by id last so pages are deterministic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sort {
+    pub field: String,
+    pub order: SortOrder,
+}
+
+impl Sort {
+    pub fn asc(field: &str) -> Self {
+        Self { field: field.to_string(), order: SortOrder::Asc }
+    }
+
+    pub fn desc(field: &str) -> Self {
+        Self { field: field.to_string(), order: SortOrder::Desc }
+    }
+}
+
+/// Value of a field, rejecting names the entity does not declare
+pub fn field_value<T: Entity>(entity: &T, field: &str) -> InfraResult<FieldValue> {
+    entity.field(field).ok_or_else(|| InfrastructureError::InvalidQuery(format!("unknown field {field}")))
+}
+
+fn any(results: Vec<Option<bool>>) -> Option<bool> {
+    if results.contains(&Some(true)) {
+        Some(true)
+    } else if results.contains(&None) {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+/// Element of a parsed `LIKE` pattern
+#[derive(Clone, Copy, PartialEq)]
+enum LikeToken {
+    /// `%`: any sequence
+    Any,
+    /// `_`: any single character
+    One,
+    Char(char),
+}
+
+/// SQL `LIKE` matching: `%` is any sequence, `_` any single character and `\` escapes the
+/// next character; like Postgres, a pattern ending in an escape is rejected
+fn like(text: &str, pattern: &str) -> InfraResult<bool> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '%' => LikeToken::Any,
+            '_' => LikeToken::One,
+            '\\' => LikeToken::Char(chars.next().ok_or_else(|| {
+                InfrastructureError::InvalidQuery(format!("LIKE pattern must not end with an escape: {pattern}"))
+            })?),
+            c => LikeToken::Char(c),
+        });
+    }
+
+    // matched[j]: the text consumed so far matches tokens[..j]
+    let mut matched = vec![false; tokens.len() + 1];
+    matched[0] = true;
+    for j in 1..=tokens.len() {
+        matched[j] = matched[j - 1] && tokens[j - 1] == LikeToken::Any;
+    }
+    for c in text.chars() {
+        let mut next = vec![false; tokens.len() + 1];
+        for j in 1..=tokens.len() {
+            next[j] = match tokens[j - 1] {
+                LikeToken::Any => next[j - 1] || matched[j],
+                LikeToken::One => matched[j - 1],
+                LikeToken::Char(p) => matched[j - 1] && p == c,
+            };
+        }
+        matched = next;
+    }
+    Ok(matched[tokens.len()])
+}
+
+/// Sort keys with the id appended as final tiebreaker unless already present
+pub fn effective_sort(sort: &[Sort], id_field: &str) -> Vec<Sort> {
+    let mut keys = sort.to_vec();
+    if !keys.iter().any(|key| key.field == id_field) {
+        keys.push(Sort::asc(id_field));
+    }
+    keys
+}
+
+/// Keyset pages compare every sort key with `<`/`>`, which never matches NULL, so they only
+/// sort on declared, non-nullable fields
+pub fn check_keyset_sort<T: Entity>(keys: &[Sort]) -> InfraResult<()> {
+    for key in keys {
+        if !T::FIELDS.contains(&key.field.as_str()) {
+            return Err(InfrastructureError::InvalidQuery(format!("unknown field {}", key.field)));
+        }
+        if T::NULLABLE.contains(&key.field.as_str()) {
+            return Err(InfrastructureError::InvalidQuery(format!("keyset pages cannot sort on nullable field {}", key.field)));
+        }
+    }
+    Ok(())
+}
+
+/// Position after the last entity of a keyset page: its sort key values, id included
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor(pub Vec<FieldValue>);
+
+impl Cursor {
+    /// Opaque, URL-safe form for API responses
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(&self.0).unwrap_or_default();
+        json.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn decode(encoded: &str) -> InfraResult<Self> {
+        let invalid = || InfrastructureError::InvalidQuery(format!("invalid cursor: {encoded}"));
+        if !encoded.len().is_multiple_of(2) {
+            return Err(invalid());
+        }
+        let bytes = (0..encoded.len())
+            .step_by(2)
+            .map(|i| encoded.get(i..i + 2).and_then(|hex| u8::from_str_radix(hex, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+        serde_json::from_slice(&bytes).map(Cursor).map_err(|_| invalid())
+    }
+}
+
+/// Which page of results to return
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageRequest {
+    /// Skip `offset` entities; simple but slows down on deep pages
+    Offset { offset: u64, limit: u64 },
+    /// Continue after `after` (from the start when `None`); stable under concurrent inserts
+    Keyset { after: Option<Cursor>, limit: u64 },
+}
+
+impl PageRequest {
+    pub fn offset(offset: u64, limit: u64) -> Self {
+        PageRequest::Offset { offset, limit }
+    }
+
+    pub fn first(limit: u64) -> Self {
+        PageRequest::Keyset { after: None, limit }
+    }
+
+    pub fn after(cursor: Cursor, limit: u64) -> Self {
+        PageRequest::Keyset { after: Some(cursor), limit }
+    }
+
+    pub fn limit(&self) -> u64 {
+        match self {
+            PageRequest::Offset { limit, .. } | PageRequest::Keyset { limit, .. } => *limit,
+        }
+    }
+}
+
+/// One page of results
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Request for the following page, `None` on the last page
+    pub next: Option<PageRequest>,
+}
+
+impl<T> Page<T> {
+    /// Encoded cursor of the following keyset page
+    pub fn next_cursor(&self) -> Option<String> {
+        match &self.next {
+            Some(PageRequest::Keyset { after: Some(cursor), .. }) => Some(cursor.encode()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::warn;
+use crate::shared::errors::{InfraResult, InfrastructureError};
+
+struct Inner {
+    tx: Mutex<Option<Transaction<'static, Postgres>>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if self.tx.get_mut().is_some() {
+            // Dropping the transaction makes sqlx roll it back
+            warn!("Unit of work dropped without commit, rolling back");
+        }
+    }
+}
+
+/// Database transaction shared by the repositories enlisted in it
+///
+/// Clones refer to the same transaction. Work is persisted only by `commit`; `rollback`,
+/// or dropping the last clone without committing, discards it.
+#[derive(Clone)]
+pub struct UnitOfWork {
+    inner: Arc<Inner>,
+}
+
+impl UnitOfWork {
+    pub async fn begin(pool: &PgPool) -> InfraResult<Self> {
+        let tx = pool.begin().await.map_err(db_error)?;
+        Ok(Self { inner: Arc::new(Inner { tx: Mutex::new(Some(tx)) }) })
+    }
+
+    /// Run `work` in a new unit of work, committing if it succeeds and rolling back otherwise
+    pub async fn run<F, Fut, R, E>(pool: &PgPool, work: F) -> Result<R, E>
+    where
+        F: FnOnce(UnitOfWork) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+        E: From<InfrastructureError>,
+    {
+        let uow = Self::begin(pool).await?;
+        match work(uow.clone()).await {
+            Ok(result) => {
+                uow.commit().await?;
+                Ok(result)
+            }
+            Err(e) => {
+                uow.rollback().await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Exclusive access to the transaction's connection, for statements outside a repository
+    pub async fn connection(&self) -> InfraResult<Connection<'_>> {
+        let guard = self.inner.tx.lock().await;
+        if guard.is_none() {
+            return Err(completed());
+        }
+        Ok(Connection::Enlisted(guard))
+    }
+
+    pub async fn commit(&self) -> InfraResult<()> {
+        let tx = self.inner.tx.lock().await.take().ok_or_else(completed)?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    pub async fn rollback(&self) -> InfraResult<()> {
+        let tx = self.inner.tx.lock().await.take().ok_or_else(completed)?;
+        tx.rollback().await.map_err(db_error)
+    }
+
+    /// True once committed or rolled back
+    pub async fn is_completed(&self) -> bool {
+        self.inner.tx.lock().await.is_none()
+    }
+}
+
+/// Connection a repository statement runs on: a pooled one, or the unit of work's transaction
+pub enum Connection<'a> {
+    Pooled(PoolConnection<Postgres>),
+    Enlisted(MutexGuard<'a, Option<Transaction<'static, Postgres>>>),
+}
+
+impl Connection<'_> {
+    pub async fn acquire(pool: &PgPool) -> InfraResult<Self> {
+        pool.acquire().await.map(Connection::Pooled).map_err(db_error)
+    }
+}
+
+impl Deref for Connection<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Connection::Pooled(conn) => conn,
+            Connection::Enlisted(tx) => tx.as_ref().expect("checked when the connection was handed out"),
+        }
+    }
+}
+
+impl DerefMut for Connection<'_> {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            Connection::Pooled(conn) => conn,
+            Connection::Enlisted(tx) => tx.as_mut().expect("checked when the connection was handed out"),
+        }
+    }
+}
+
+/// Misuse of a finished unit of work, distinct from database failures
+fn completed() -> InfrastructureError {
+    InfrastructureError::InvalidQuery("unit of work already committed or rolled back".to_string())
+}
+
+fn db_error(e: sqlx::Error) -> InfrastructureError {
+    InfrastructureError::Database(e.to_string())
+}
@@ -5,6 +5,8 @@ pub enum DomainError {
     #[error("validation error: {0}")] Validation(String),
     #[error("not found: {0}")] NotFound(String),
     #[error("concurrency error: {0}")] Concurrency(String),
+    #[error("stream deleted: {0}")] StreamDeleted(String),
+    #[error("persistence error: {0}")] Persistence(String),
 }
 
 #[derive(Debug, Error)]
@@ -18,11 +20,17 @@ pub enum ApplicationError {
 pub enum InfrastructureError {
     #[error("database error: {0}")] Database(String),
     #[error("event store error: {0}")] EventStore(String),
+    #[error("concurrency error: {0}")] Concurrency(String),
+    #[error("stream deleted: {0}")] StreamDeleted(String),
+    #[error("not found: {0}")] NotFound(String),
+    #[error("duplicate key: {0}")] DuplicateKey(String),
+    #[error("invalid query: {0}")] InvalidQuery(String),
     #[error("io error: {0}")] Io(String),
     #[error("messaging error: {0}")] Messaging(String),
     #[error("websocket error: {0}")] WebSocket(String),
     #[error("kafka error: {0}")] Kafka(String),
     #[error("serialization error: {0}")] Serialization(String),
+    #[error("stale format: {0}")] StaleFormat(String),
 }
 
 pub type DomainResult<T> = Result<T, DomainError>;
@@ -2,15 +2,61 @@ use uuid::Uuid;
 
 pub type EventVersion = u64;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// Version of an event's payload schema, starting at 1
+pub type SchemaVersion = u32;
+
+/// Position of an event in the store-wide log (1-based, assigned in commit order)
+pub type EventPosition = u64;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct EventMetadata {
     pub correlation_id: Option<Uuid>,
     pub causation_id: Option<Uuid>,
     pub user_id: Option<Uuid>,
 }
 
-impl Default for EventMetadata {
-    fn default() -> Self { Self { correlation_id: None, causation_id: None, user_id: None } }
+/// Stream version a writer expects when appending, used for optimistic concurrency
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpectedVersion {
+    /// Append regardless of the current stream version
+    #[default]
+    Any,
+    /// The stream must not contain any events yet
+    NoStream,
+    /// The stream must already contain at least one event
+    StreamExists,
+    /// The last event in the stream must have exactly this sequence
+    Exact(EventVersion),
+}
+
+impl ExpectedVersion {
+    /// Returns true when a stream currently at `current` satisfies this expectation (0 = empty stream)
+    pub fn matches(&self, current: EventVersion) -> bool {
+        match self {
+            ExpectedVersion::Any => true,
+            ExpectedVersion::NoStream => current == 0,
+            ExpectedVersion::StreamExists => current > 0,
+            ExpectedVersion::Exact(v) => current == *v,
+        }
+    }
+}
+
+/// Aggregate together with the stream positions it was loaded at
+#[derive(Debug, Clone)]
+pub struct LoadedAggregate<A> {
+    pub aggregate_id: Uuid,
+    pub aggregate: A,
+    /// Sequence of the last persisted event applied (0 for a new stream)
+    pub version: EventVersion,
+    /// Sequence covered by the latest snapshot of this aggregate (0 if none)
+    pub snapshot_version: EventVersion,
+}
+
+impl<A> LoadedAggregate<A> {
+    /// Wrap an aggregate that has not been persisted yet
+    pub fn new(aggregate_id: Uuid, aggregate: A) -> Self {
+        Self { aggregate_id, aggregate, version: 0, snapshot_version: 0 }
+    }
 }
 
 pub trait Versioned {
@@ -21,10 +67,17 @@ pub trait Versioned {
 pub struct EventEnvelope<T> {
     pub aggregate_id: Uuid,
     pub sequence: EventVersion,
+    #[serde(default)]
+    pub position: EventPosition,
     pub event_type: String,
+    #[serde(default = "initial_schema_version")]
+    pub schema_version: SchemaVersion,
     pub payload: T,
     pub metadata: EventMetadata,
     pub timestamp: DateTime<Utc>,
 }
+
+fn initial_schema_version() -> SchemaVersion { 1 }
+
 use chrono::{DateTime, Utc};
 
@@ -0,0 +1,143 @@
+use std::time::Duration;
+use chrono::{TimeZone, Utc};
+use rdkafka::message::{Headers, OwnedHeaders, OwnedMessage, Timestamp};
+use rdkafka::Message;
+use serde_json::json;
+use project_struct_base::infrastructure::messaging::kafka::common::{
+    ORIGINAL_OFFSET_HEADER, ORIGINAL_PARTITION_HEADER, ORIGINAL_TOPIC_HEADER,
+};
+use project_struct_base::infrastructure::messaging::kafka::consumers::retry_policy::{
+    self, ERROR_HEADER, RETRY_ATTEMPTS_HEADER, RETRY_NOT_BEFORE_HEADER,
+};
+use project_struct_base::infrastructure::messaging::{ConsumedMessage, KafkaConsumerConfig, RetryPolicy, RetryTopic};
+
+fn policy(retry_topics: &[(&str, u64)], dead_letter_topic: Option<&str>) -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 5,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(1000),
+        retry_topics: retry_topics
+            .iter()
+            .map(|(topic, delay_ms)| RetryTopic { topic: topic.to_string(), delay_ms: *delay_ms })
+            .collect(),
+        dead_letter_topic: dead_letter_topic.map(str::to_string),
+    }
+}
+
+fn message(topic: &str, partition: i32, offset: i64, headers: Option<OwnedHeaders>) -> OwnedMessage {
+    OwnedMessage::new(Some(b"{}".to_vec()), None, topic.to_string(), Timestamp::NotAvailable, partition, offset, headers)
+}
+
+fn header_list(headers: &OwnedHeaders) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|h| (h.key.to_string(), String::from_utf8_lossy(h.value.unwrap_or_default()).into_owned()))
+        .collect()
+}
+
+fn header<'a>(headers: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    headers.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let policy = policy(&[], None);
+    let backoffs: Vec<_> = (1..=6).map(|retry| policy.backoff(retry).as_millis()).collect();
+    assert_eq!(backoffs, [100, 200, 400, 800, 1000, 1000]);
+    assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(1000));
+}
+
+#[test]
+fn from_config_reads_retry_settings() {
+    let config: KafkaConsumerConfig = serde_json::from_value(json!({
+        "brokers": "localhost:9092",
+        "group_id": "test",
+        "topics": ["orders"],
+        "max_retries": 3,
+        "retry_backoff_ms": 1000,
+        "max_retry_backoff_ms": 4000,
+        "retry_topics": [{ "topic": "orders-retry", "delay_ms": 60000 }],
+        "dead_letter_topic": "orders-dlt",
+    }))
+    .unwrap();
+    let policy = RetryPolicy::from_config(&config);
+    let backoffs: Vec<_> = (1..=4).map(|retry| policy.backoff(retry).as_secs()).collect();
+    assert_eq!(backoffs, [1, 2, 4, 4]);
+    assert_eq!(policy.next_topic("orders"), Some(("orders-retry", Some(Duration::from_secs(60)))));
+    assert_eq!(policy.next_topic("orders-retry"), Some(("orders-dlt", None)));
+}
+
+#[test]
+fn next_topic_walks_retry_topics_then_dead_letters() {
+    let policy = policy(&[("orders-retry-1", 1000), ("orders-retry-2", 60000)], Some("orders-dlt"));
+    assert!(policy.forwards());
+    assert_eq!(policy.next_topic("orders"), Some(("orders-retry-1", Some(Duration::from_secs(1)))));
+    assert_eq!(policy.next_topic("orders-retry-1"), Some(("orders-retry-2", Some(Duration::from_secs(60)))));
+    assert_eq!(policy.next_topic("orders-retry-2"), Some(("orders-dlt", None)));
+}
+
+#[test]
+fn next_topic_without_retry_topics_goes_to_dead_letter_or_nowhere() {
+    let policy_with_dlt = policy(&[], Some("orders-dlt"));
+    assert_eq!(policy_with_dlt.next_topic("orders"), Some(("orders-dlt", None)));
+
+    let last_stage = policy(&[("orders-retry", 1000)], None);
+    assert_eq!(last_stage.next_topic("orders-retry"), None);
+
+    let neither = policy(&[], None);
+    assert!(!neither.forwards());
+    assert_eq!(neither.next_topic("orders"), None);
+}
+
+#[test]
+fn forwarded_headers_round_trip() {
+    let not_before = Utc.timestamp_millis_opt(1_700_000_000_123).unwrap();
+    let original = message(
+        "orders",
+        3,
+        42,
+        Some(OwnedHeaders::new().insert(rdkafka::message::Header { key: "trace-id", value: Some("abc") })),
+    );
+    assert_eq!(retry_policy::previous_attempts(&original), 0);
+    assert_eq!(retry_policy::not_before(&original), None);
+
+    let headers = retry_policy::retry_headers(&original, 2, "boom", Some(not_before));
+    let first = header_list(&headers);
+    assert_eq!(header(&first, "trace-id"), Some("abc"));
+    assert_eq!(header(&first, ORIGINAL_TOPIC_HEADER), Some("orders"));
+    assert_eq!(header(&first, ORIGINAL_PARTITION_HEADER), Some("3"));
+    assert_eq!(header(&first, ORIGINAL_OFFSET_HEADER), Some("42"));
+    assert_eq!(header(&first, ERROR_HEADER), Some("boom"));
+
+    let retried = message("orders-retry-1", 0, 7, Some(headers));
+    assert_eq!(retry_policy::previous_attempts(&retried), 2);
+    assert_eq!(retry_policy::not_before(&retried), Some(not_before));
+
+    // A second forward keeps the first position and replaces the retry headers
+    let headers = retry_policy::retry_headers(&retried, 3, "boom again", None);
+    let second = header_list(&headers);
+    for key in [RETRY_ATTEMPTS_HEADER, RETRY_NOT_BEFORE_HEADER, ERROR_HEADER, ORIGINAL_TOPIC_HEADER] {
+        assert!(second.iter().filter(|(k, _)| k == key).count() <= 1, "duplicate {} header", key);
+    }
+    assert_eq!(header(&second, ORIGINAL_TOPIC_HEADER), Some("orders"));
+    assert_eq!(header(&second, ORIGINAL_OFFSET_HEADER), Some("42"));
+    assert_eq!(header(&second, ERROR_HEADER), Some("boom again"));
+
+    let dead = message("orders-dlt", 1, 9, Some(headers));
+    assert_eq!(retry_policy::previous_attempts(&dead), 3);
+    assert_eq!(retry_policy::not_before(&dead), None);
+
+    // Deduplication sees the same id on every topic the message passes through
+    let consumed = |m: &OwnedMessage| ConsumedMessage {
+        key: None,
+        value: (),
+        headers: m.headers().map(header_list).unwrap_or_default(),
+        topic: m.topic().to_string(),
+        partition: m.partition(),
+        offset: m.offset(),
+        timestamp: None,
+    };
+    assert_eq!(consumed(&original).message_id(), "orders/3/42");
+    assert_eq!(consumed(&retried).message_id(), "orders/3/42");
+    assert_eq!(consumed(&dead).message_id(), "orders/3/42");
+}
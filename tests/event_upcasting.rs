@@ -0,0 +1,83 @@
+use chrono::Utc;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use project_struct_base::application::ports::EventStore;
+use project_struct_base::infrastructure::event_store::{InMemoryEventStore, UpcasterRegistry};
+use project_struct_base::shared::types::{EventEnvelope, EventMetadata, ExpectedVersion};
+
+/// v1 `{ "name": "Ada Lovelace" }` -> v2 splits the name -> v3 nests it under `person`
+fn registry() -> UpcasterRegistry {
+    UpcasterRegistry::new()
+        .register("UserRegistered", 2, |mut payload| {
+            let first = payload["first_name"].take();
+            let last = payload["last_name"].take();
+            Ok(json!({ "person": { "first_name": first, "last_name": last } }))
+        })
+        .register("UserRegistered", 1, |payload| {
+            let name = payload["name"].as_str().unwrap_or_default().to_string();
+            let (first, last) = name.split_once(' ').unwrap_or((name.as_str(), ""));
+            Ok(json!({ "first_name": first, "last_name": last }))
+        })
+}
+
+fn stored_v1(aggregate_id: Uuid, sequence: u64, payload: Value) -> EventEnvelope<Value> {
+    EventEnvelope {
+        aggregate_id,
+        sequence,
+        position: 0,
+        event_type: "UserRegistered".into(),
+        schema_version: 1,
+        payload,
+        metadata: EventMetadata::default(),
+        timestamp: Utc::now(),
+    }
+}
+
+#[tokio::test]
+async fn read_stream_runs_upcaster_chain_in_version_order() {
+    let store = InMemoryEventStore::new().with_upcasters(registry());
+    let id = Uuid::new_v4();
+    store.seed(id, vec![stored_v1(id, 1, json!({ "name": "Ada Lovelace" }))]).await;
+
+    let events = store.read_stream(id).await.unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].schema_version, 3);
+    assert_eq!(events[0].payload, json!({ "person": { "first_name": "Ada", "last_name": "Lovelace" } }));
+}
+
+#[tokio::test]
+async fn read_all_upcasts_old_events_and_leaves_current_ones_untouched() {
+    let store = InMemoryEventStore::new().with_upcasters(registry());
+    let id = Uuid::new_v4();
+    store.seed(id, vec![stored_v1(id, 1, json!({ "name": "Alan Turing" }))]).await;
+
+    let current = json!({ "person": { "first_name": "Grace", "last_name": "Hopper" } });
+    let appended = store
+        .append(id, ExpectedVersion::Exact(1), vec![("UserRegistered".into(), current.clone())], EventMetadata::default())
+        .await
+        .unwrap();
+    assert_eq!(appended[0].schema_version, 3);
+
+    let events = store.read_all(0, 10).await.unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].payload, json!({ "person": { "first_name": "Alan", "last_name": "Turing" } }));
+    assert_eq!(events[1].payload, current);
+}
+
+#[tokio::test]
+async fn event_types_without_upcasters_are_returned_as_stored() {
+    let store = InMemoryEventStore::new().with_upcasters(registry());
+    let id = Uuid::new_v4();
+    let payload = json!({ "reason": "requested" });
+    store
+        .append(id, ExpectedVersion::NoStream, vec![("UserDeactivated".into(), payload.clone())], EventMetadata::default())
+        .await
+        .unwrap();
+
+    let events = store.read_stream(id).await.unwrap();
+
+    assert_eq!(events[0].schema_version, 1);
+    assert_eq!(events[0].payload, payload);
+}
@@ -0,0 +1,150 @@
+use futures::StreamExt;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use project_struct_base::application::ports::EventStore;
+use project_struct_base::infrastructure::event_store::InMemoryEventStore;
+use project_struct_base::shared::errors::{InfraResult, InfrastructureError};
+use project_struct_base::shared::types::{EventEnvelope, ExpectedVersion};
+
+async fn append(
+    store: &InMemoryEventStore,
+    aggregate_id: Uuid,
+    expected: ExpectedVersion,
+    count: usize,
+) -> InfraResult<Vec<EventEnvelope<Value>>> {
+    let events = (0..count).map(|i| ("Happened".to_string(), json!({ "i": i }))).collect();
+    store.append(aggregate_id, expected, events, Default::default()).await
+}
+
+fn is_conflict<T>(result: InfraResult<T>) -> bool {
+    matches!(result, Err(InfrastructureError::Concurrency(_)))
+}
+
+#[tokio::test]
+async fn any_appends_to_new_and_existing_streams() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    append(&store, id, ExpectedVersion::Any, 2).await.unwrap();
+    let appended = append(&store, id, ExpectedVersion::Any, 1).await.unwrap();
+    assert_eq!(appended[0].sequence, 3);
+}
+
+#[tokio::test]
+async fn no_stream_only_creates_streams() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    let appended = append(&store, id, ExpectedVersion::NoStream, 2).await.unwrap();
+    assert_eq!(appended.iter().map(|e| e.sequence).collect::<Vec<_>>(), [1, 2]);
+
+    assert!(is_conflict(append(&store, id, ExpectedVersion::NoStream, 1).await));
+    assert_eq!(store.read_stream(id).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn stream_exists_requires_events() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    assert!(is_conflict(append(&store, id, ExpectedVersion::StreamExists, 1).await));
+    assert!(store.read_stream(id).await.unwrap().is_empty());
+
+    append(&store, id, ExpectedVersion::NoStream, 1).await.unwrap();
+    let appended = append(&store, id, ExpectedVersion::StreamExists, 1).await.unwrap();
+    assert_eq!(appended[0].sequence, 2);
+}
+
+#[tokio::test]
+async fn exact_rejects_stale_and_future_versions() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    assert!(is_conflict(append(&store, id, ExpectedVersion::Exact(1), 1).await));
+    append(&store, id, ExpectedVersion::Exact(0), 2).await.unwrap();
+
+    // Two writers that both loaded version 2: the second one loses
+    append(&store, id, ExpectedVersion::Exact(2), 1).await.unwrap();
+    assert!(is_conflict(append(&store, id, ExpectedVersion::Exact(2), 1).await));
+    assert!(is_conflict(append(&store, id, ExpectedVersion::Exact(4), 1).await));
+
+    let sequences: Vec<_> = store.read_stream(id).await.unwrap().iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, [1, 2, 3]);
+}
+
+#[tokio::test]
+async fn rejected_appends_write_nothing() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    append(&store, id, ExpectedVersion::NoStream, 1).await.unwrap();
+    assert!(is_conflict(append(&store, id, ExpectedVersion::Exact(0), 3).await));
+    assert_eq!(store.event_count().await, 1);
+    assert_eq!(store.last_position().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn instances_do_not_share_streams_but_clones_do() {
+    let first = InMemoryEventStore::new();
+    let second = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    append(&first, id, ExpectedVersion::NoStream, 2).await.unwrap();
+
+    assert!(second.read_stream(id).await.unwrap().is_empty());
+    assert_eq!(second.last_position().await.unwrap(), 0);
+    // The same id starts a fresh stream in another instance
+    assert_eq!(append(&second, id, ExpectedVersion::NoStream, 1).await.unwrap()[0].position, 1);
+
+    let clone = first.clone();
+    append(&clone, id, ExpectedVersion::Exact(2), 1).await.unwrap();
+    assert_eq!(first.read_stream(id).await.unwrap().len(), 3);
+    assert_eq!(first.stream_ids().await, [id]);
+}
+
+#[tokio::test]
+async fn clear_resets_versions_and_positions() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    append(&store, id, ExpectedVersion::NoStream, 3).await.unwrap();
+    store.tombstone_stream(Uuid::new_v4()).await.unwrap();
+
+    store.clear().await;
+    assert!(store.stream_ids().await.is_empty());
+    assert_eq!(store.event_count().await, 0);
+    assert_eq!(store.last_position().await.unwrap(), 0);
+    assert!(store.tombstoned_ids().await.is_empty());
+
+    let appended = append(&store, id, ExpectedVersion::NoStream, 1).await.unwrap();
+    assert_eq!((appended[0].sequence, appended[0].position), (1, 1));
+}
+
+#[tokio::test]
+async fn seed_replaces_a_stream_and_lifts_its_tombstone() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    let original = append(&store, id, ExpectedVersion::NoStream, 3).await.unwrap();
+    store.tombstone_stream(id).await.unwrap();
+
+    store.seed(id, original.into_iter().take(2).collect()).await;
+
+    let stream = store.read_stream(id).await.unwrap();
+    assert_eq!(stream.iter().map(|e| e.sequence).collect::<Vec<_>>(), [1, 2]);
+    // Seeded events take fresh positions after the ones already handed out
+    assert_eq!(stream.iter().map(|e| e.position).collect::<Vec<_>>(), [4, 5]);
+    assert_eq!(store.event_count().await, 2);
+    assert!(store.tombstoned_ids().await.is_empty());
+    append(&store, id, ExpectedVersion::Exact(2), 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_subscriptions_catch_up_on_long_streams_then_go_live() {
+    let store = InMemoryEventStore::new();
+    let id = Uuid::new_v4();
+    // Longer than one catch-up page, interleaved with another stream
+    for _ in 0..3 {
+        append(&store, id, ExpectedVersion::Any, 250).await.unwrap();
+        append(&store, Uuid::new_v4(), ExpectedVersion::Any, 5).await.unwrap();
+    }
+
+    let mut subscription = store.subscribe_stream(id, 10).await.unwrap();
+    for expected in 11..=750 {
+        assert_eq!(subscription.next().await.unwrap().unwrap().sequence, expected);
+    }
+    append(&store, id, ExpectedVersion::Exact(750), 1).await.unwrap();
+    assert_eq!(subscription.next().await.unwrap().unwrap().sequence, 751);
+}
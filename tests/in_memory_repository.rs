@@ -0,0 +1,201 @@
+use project_struct_base::entity;
+use project_struct_base::infrastructure::repositories::{
+    Cursor, FieldValue, InMemoryRepository, PageRequest, Repository, Sort, Specification,
+};
+use project_struct_base::shared::errors::InfrastructureError;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Person {
+    id: i64,
+    name: String,
+    age: Option<i32>,
+    version: i64,
+}
+
+entity!(Person, id = id, fields = [name, age], nullable = [age], version = version);
+
+#[derive(Clone, Debug, PartialEq)]
+struct Score {
+    id: i64,
+    team: String,
+    points: i64,
+}
+
+entity!(Score, id = id, fields = [team, points]);
+
+fn person(id: i64, name: &str, age: Option<i32>) -> Person {
+    Person { id, name: name.to_string(), age, version: 0 }
+}
+
+async fn ids(repo: &InMemoryRepository<Person>, spec: Specification, sort: &[Sort]) -> Vec<i64> {
+    let page = repo.find_page(&spec, sort, &PageRequest::offset(0, 100)).await.unwrap();
+    page.items.iter().map(|p| p.id).collect()
+}
+
+#[tokio::test]
+async fn save_update_and_delete_report_conflicts() {
+    let repo = InMemoryRepository::new();
+    let mut ada = person(1, "Ada", Some(36));
+    repo.save(&ada).await.unwrap();
+    assert!(matches!(repo.save(&ada).await, Err(InfrastructureError::DuplicateKey(id)) if id == "1"));
+
+    ada.name = "Ada Lovelace".to_string();
+    repo.update(&ada).await.unwrap();
+    let stored = repo.find_by_id("1").await.unwrap().unwrap();
+    assert_eq!((stored.name.as_str(), stored.version), ("Ada Lovelace", 1));
+    // `ada` still carries version 0
+    assert!(matches!(repo.update(&ada).await, Err(InfrastructureError::Concurrency(_))));
+
+    let missing = person(2, "Grace", None);
+    assert!(matches!(repo.update(&missing).await, Err(InfrastructureError::NotFound(_))));
+    assert!(matches!(repo.delete("2").await, Err(InfrastructureError::NotFound(_))));
+
+    repo.delete("1").await.unwrap();
+    assert_eq!(repo.find_by_id("1").await.unwrap(), None);
+    assert!(matches!(repo.delete("1").await, Err(InfrastructureError::NotFound(_))));
+    assert!(matches!(repo.update(&stored).await, Err(InfrastructureError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn comparisons_with_null_never_match() {
+    let repo = InMemoryRepository::with_entities([person(1, "a", Some(30)), person(2, "b", None)]);
+
+    assert_eq!(ids(&repo, Specification::eq("age", 30), &[]).await, [1]);
+    assert_eq!(ids(&repo, Specification::ne("age", 30), &[]).await, Vec::<i64>::new());
+    assert_eq!(ids(&repo, Specification::lt("age", 100), &[]).await, [1]);
+    // NOT of unknown is still unknown
+    assert_eq!(ids(&repo, Specification::eq("age", 30).negate(), &[]).await, Vec::<i64>::new());
+    assert_eq!(ids(&repo, Specification::eq("age", FieldValue::Null), &[]).await, Vec::<i64>::new());
+    assert_eq!(ids(&repo, Specification::is_in("age", [30, 40]).negate(), &[]).await, Vec::<i64>::new());
+
+    assert_eq!(ids(&repo, Specification::is_null("age"), &[]).await, [2]);
+    assert_eq!(ids(&repo, Specification::is_null("age").negate(), &[]).await, [1]);
+    // unknown OR true is true; NOT (unknown AND true) stays unknown
+    assert_eq!(ids(&repo, Specification::eq("age", 30).or(Specification::eq("name", "b")), &[]).await, [1, 2]);
+    assert_eq!(
+        ids(&repo, Specification::eq("age", 30).and(Specification::eq("name", "b")).negate(), &[]).await,
+        [1]
+    );
+}
+
+#[tokio::test]
+async fn like_supports_wildcards_and_escapes() {
+    let repo = InMemoryRepository::with_entities([
+        person(1, "50%", None),
+        person(2, "50 off", None),
+        person(3, "a_b", None),
+        person(4, "axb", None),
+        person(5, r"back\slash", None),
+    ]);
+
+    assert_eq!(ids(&repo, Specification::like("name", "50%"), &[]).await, [1, 2]);
+    assert_eq!(ids(&repo, Specification::like("name", r"50\%"), &[]).await, [1]);
+    assert_eq!(ids(&repo, Specification::like("name", "a_b"), &[]).await, [3, 4]);
+    assert_eq!(ids(&repo, Specification::like("name", r"a\_b"), &[]).await, [3]);
+    assert_eq!(ids(&repo, Specification::like("name", "%off"), &[]).await, [2]);
+    assert_eq!(ids(&repo, Specification::like("name", "_0%"), &[]).await, [1, 2]);
+    assert_eq!(ids(&repo, Specification::like("name", r"%\\%"), &[]).await, [5]);
+    assert_eq!(ids(&repo, Specification::like("name", "axb_"), &[]).await, Vec::<i64>::new());
+
+    let trailing_escape = repo.count(&Specification::like("name", r"50\")).await;
+    assert!(matches!(trailing_escape, Err(InfrastructureError::InvalidQuery(_))));
+}
+
+#[tokio::test]
+async fn nulls_sort_last_ascending_and_first_descending() {
+    let repo = InMemoryRepository::with_entities([
+        person(1, "a", Some(30)),
+        person(2, "b", None),
+        person(3, "c", Some(20)),
+        person(4, "d", Some(30)),
+    ]);
+
+    assert_eq!(ids(&repo, Specification::All, &[Sort::asc("age")]).await, [3, 1, 4, 2]);
+    assert_eq!(ids(&repo, Specification::All, &[Sort::desc("age")]).await, [2, 1, 4, 3]);
+    assert_eq!(ids(&repo, Specification::All, &[Sort::desc("age"), Sort::desc("id")]).await, [2, 4, 1, 3]);
+}
+
+#[tokio::test]
+async fn offset_and_keyset_pages_cover_every_match_once() {
+    // Teams repeat, and some teams repeat points, so every key needs the next one to break ties
+    let repo = InMemoryRepository::with_entities(
+        (1..=10).map(|i| Score { id: i, team: format!("t{}", i % 3), points: i % 2 }),
+    );
+    let sort = [Sort::asc("team"), Sort::desc("points")];
+    let page = repo.find_page(&Specification::All, &sort, &PageRequest::offset(0, 100)).await.unwrap();
+    let full: Vec<i64> = page.items.iter().map(|s| s.id).collect();
+    assert_eq!(full, [3, 9, 6, 1, 7, 4, 10, 5, 2, 8]);
+
+    let mut offset_ids = Vec::new();
+    let mut request = PageRequest::offset(0, 3);
+    loop {
+        let page = repo.find_page(&Specification::All, &sort, &request).await.unwrap();
+        assert!(page.items.len() <= 3);
+        offset_ids.extend(page.items.iter().map(|p| p.id));
+        match page.next {
+            Some(next) => request = next,
+            None => break,
+        }
+    }
+    assert_eq!(offset_ids, full);
+
+    let mut keyset_ids = Vec::new();
+    let mut request = PageRequest::first(3);
+    loop {
+        let page = repo.find_page(&Specification::All, &sort, &request).await.unwrap();
+        keyset_ids.extend(page.items.iter().map(|p| p.id));
+        let Some(cursor) = page.next_cursor() else {
+            assert_eq!(page.next, None);
+            break;
+        };
+        // The encoded cursor continues exactly where the page left off
+        request = PageRequest::after(Cursor::decode(&cursor).unwrap(), 3);
+        assert_eq!(Some(&request), page.next.as_ref());
+    }
+    assert_eq!(keyset_ids, full);
+}
+
+#[tokio::test]
+async fn malformed_cursors_are_invalid_queries() {
+    for encoded in ["abc", "zz", "7b7d"] {
+        assert!(matches!(Cursor::decode(encoded), Err(InfrastructureError::InvalidQuery(_))), "{encoded}");
+    }
+
+    let repo = InMemoryRepository::with_entities([person(1, "a", Some(1))]);
+    // Sorting by name then id needs a two-value cursor
+    let short = PageRequest::after(Cursor(vec![FieldValue::from("a")]), 10);
+    let result = repo.find_page(&Specification::All, &[Sort::asc("name")], &short).await;
+    assert!(matches!(result, Err(InfrastructureError::InvalidQuery(_))));
+}
+
+#[tokio::test]
+async fn unknown_fields_are_rejected_even_when_empty() {
+    let repo: InMemoryRepository<Person> = InMemoryRepository::new();
+    let unknown = Specification::eq("nickname", "x");
+    assert!(matches!(repo.count(&unknown).await, Err(InfrastructureError::InvalidQuery(_))));
+    assert!(matches!(
+        repo.find_page(&Specification::All, &[Sort::asc("nickname")], &PageRequest::first(1)).await,
+        Err(InfrastructureError::InvalidQuery(_))
+    ));
+
+    repo.save(&person(1, "a", None)).await.unwrap();
+    let nested = Specification::eq("name", "a").or(unknown.negate());
+    assert!(matches!(
+        repo.find_page(&nested, &[], &PageRequest::offset(0, 1)).await,
+        Err(InfrastructureError::InvalidQuery(_))
+    ));
+}
+
+#[tokio::test]
+async fn keyset_pages_refuse_nullable_sort_fields() {
+    let repo = InMemoryRepository::with_entities([person(1, "a", Some(1)), person(2, "b", None)]);
+    let by_age = [Sort::asc("name"), Sort::desc("age")];
+    for request in [PageRequest::first(1), PageRequest::after(Cursor(vec!["a".into(), 1.into(), 1i64.into()]), 1)] {
+        assert!(matches!(
+            repo.find_page(&Specification::All, &by_age, &request).await,
+            Err(InfrastructureError::InvalidQuery(_))
+        ));
+    }
+    // Offset pages order NULLs like Postgres and may sort on them
+    assert_eq!(ids(&repo, Specification::All, &by_age).await, [1, 2]);
+}
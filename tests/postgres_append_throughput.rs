@@ -0,0 +1,48 @@
+//! Append throughput of `PostgresEventStore` under the store-wide append lock.
+//!
+//! Needs a migrated database:
+//! `DATABASE_URL=postgres://... cargo test --release --test postgres_append_throughput -- --ignored --nocapture`
+
+use std::time::Instant;
+use serde_json::json;
+use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
+use project_struct_base::application::ports::EventStore;
+use project_struct_base::infrastructure::event_store::PostgresEventStore;
+use project_struct_base::shared::types::{EventMetadata, ExpectedVersion};
+
+const APPENDS: usize = 2000;
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs DATABASE_URL pointing at a migrated database"]
+async fn appends_per_second_by_concurrent_writers() {
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL");
+    let pool = PgPoolOptions::new().max_connections(32).connect(&url).await.unwrap();
+    let store = PostgresEventStore::new(pool);
+
+    for writers in [1, 4, 16, 32] {
+        let per_writer = APPENDS / writers;
+        let started = Instant::now();
+        let tasks: Vec<_> = (0..writers)
+            .map(|_| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    // One stream per writer, so only the global lock makes them contend
+                    let aggregate_id = Uuid::new_v4();
+                    for i in 0..per_writer {
+                        let event = vec![("Benchmarked".to_string(), json!({ "i": i }))];
+                        store
+                            .append(aggregate_id, ExpectedVersion::Exact(i as u64), event, EventMetadata::default())
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+        let appends = per_writer * writers;
+        println!("writers={writers} appends={appends} per_sec={:.0}", appends as f64 / started.elapsed().as_secs_f64());
+    }
+}
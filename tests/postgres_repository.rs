@@ -0,0 +1,65 @@
+//! Needs a database: `DATABASE_URL=postgres://... cargo test --test postgres_repository -- --ignored`
+
+use sqlx::PgPool;
+use project_struct_base::pg_entity;
+use project_struct_base::infrastructure::repositories::{
+    Cursor, PageRequest, PostgresRepository, Repository, Sort, Specification,
+};
+use project_struct_base::shared::errors::InfrastructureError;
+
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+struct Score {
+    id: i64,
+    team: String,
+    points: i64,
+    note: Option<String>,
+}
+
+pg_entity!(Score, table = "keyset_scores", id = id: "bigint", columns = [team, points, note], nullable = [note]);
+
+async fn repository() -> PostgresRepository {
+    let pool = PgPool::connect(&std::env::var("DATABASE_URL").expect("DATABASE_URL")).await.unwrap();
+    sqlx::query("DROP TABLE IF EXISTS keyset_scores").execute(&pool).await.unwrap();
+    sqlx::query("CREATE TABLE keyset_scores (id BIGINT PRIMARY KEY, team TEXT NOT NULL, points BIGINT NOT NULL, note TEXT)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let repo = PostgresRepository::new(pool);
+    for i in 1..=10 {
+        let note = (i % 4 == 0).then(|| format!("note {i}"));
+        Repository::<Score>::save(&repo, &Score { id: i, team: format!("t{}", i % 3), points: i % 2, note }).await.unwrap();
+    }
+    repo
+}
+
+fn is_invalid<T>(result: Result<T, InfrastructureError>) -> bool {
+    matches!(result, Err(InfrastructureError::InvalidQuery(_)))
+}
+
+#[tokio::test]
+#[ignore = "needs DATABASE_URL"]
+async fn keyset_pages_break_ties_and_refuse_nullable_sort_fields() {
+    let repo = repository().await;
+    // Teams repeat, and some teams repeat points, so every key needs the next one to break ties
+    let sort = [Sort::asc("team"), Sort::desc("points")];
+
+    let mut ids = Vec::new();
+    let mut request = PageRequest::first(2);
+    loop {
+        let page = Repository::<Score>::find_page(&repo, &Specification::All, &sort, &request).await.unwrap();
+        ids.extend(page.items.iter().map(|s| s.id));
+        let Some(cursor) = page.next_cursor() else { break };
+        request = PageRequest::after(Cursor::decode(&cursor).unwrap(), 2);
+    }
+    assert_eq!(ids, [3, 9, 6, 1, 7, 4, 10, 5, 2, 8]);
+
+    for sort in [Sort::asc("note"), Sort::asc("nickname")] {
+        let sort = [Sort::asc("team"), sort];
+        assert!(is_invalid(Repository::<Score>::find_page(&repo, &Specification::All, &sort, &PageRequest::first(3)).await));
+    }
+
+    // Offset pages may still sort on nullable fields
+    let by_note = [Sort::asc("note")];
+    let page = Repository::<Score>::find_page(&repo, &Specification::All, &by_note, &PageRequest::offset(0, 3)).await.unwrap();
+    assert_eq!(page.items.iter().map(|s| s.id).collect::<Vec<_>>(), [4, 8, 1]);
+}
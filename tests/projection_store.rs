@@ -0,0 +1,120 @@
+use std::sync::Arc;
+use std::time::Duration;
+use project_struct_base::application::ports::ProjectionStore;
+use project_struct_base::infrastructure::projection_store::{ExpirySweeper, InMemoryProjectionStore};
+use project_struct_base::shared::errors::InfrastructureError;
+
+const SHORT_TTL: Duration = Duration::from_millis(30);
+
+async fn outlive_short_ttl() {
+    tokio::time::sleep(SHORT_TTL * 2).await;
+}
+
+#[tokio::test]
+async fn compare_and_set_rejects_revision_mismatches() {
+    let store = InMemoryProjectionStore::new();
+    assert_eq!(store.compare_and_set("k", None, b"a", None).await.unwrap(), 1);
+    assert!(matches!(store.compare_and_set("k", None, b"b", None).await, Err(InfrastructureError::Concurrency(_))));
+    assert!(matches!(store.compare_and_set("k", Some(2), b"b", None).await, Err(InfrastructureError::Concurrency(_))));
+    assert_eq!(store.compare_and_set("k", Some(1), b"b", None).await.unwrap(), 2);
+
+    // Plain saves bump the revision too
+    store.save("k", b"c").await.unwrap();
+    let entry = store.get_versioned("k").await.unwrap().unwrap();
+    assert_eq!((entry.value.as_slice(), entry.revision), (b"c".as_slice(), 3));
+
+    assert!(matches!(store.compare_and_delete("k", 2).await, Err(InfrastructureError::Concurrency(_))));
+    store.compare_and_delete("k", 3).await.unwrap();
+    assert!(matches!(store.compare_and_delete("k", 3).await, Err(InfrastructureError::Concurrency(_))));
+    assert_eq!(store.compare_and_set("k", None, b"d", None).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn expired_entries_are_invisible_to_reads() {
+    let store = InMemoryProjectionStore::new();
+    store.save_with_ttl("session/1", b"a", SHORT_TTL).await.unwrap();
+    store.save("session/2", b"b").await.unwrap();
+    let entry = store.get_versioned("session/1").await.unwrap().unwrap();
+    assert!(entry.expires_at.is_some());
+    assert_eq!(store.scan_prefix("session/", 10).await.unwrap().len(), 2);
+
+    outlive_short_ttl().await;
+    assert_eq!(store.get("session/1").await.unwrap(), None);
+    assert_eq!(store.get_versioned("session/1").await.unwrap(), None);
+    let keys = ["session/1".to_string(), "session/2".to_string()];
+    assert_eq!(store.get_many(&keys).await.unwrap(), [None, Some(b"b".to_vec())]);
+    let scanned: Vec<String> = store.scan_prefix("session/", 10).await.unwrap().into_iter().map(|(key, _)| key).collect();
+    assert_eq!(scanned, ["session/2"]);
+    assert_eq!(store.scan_range("session/", "session0", 10).await.unwrap().len(), 1);
+    // Still stored until purged
+    assert_eq!(store.len().await, 2);
+}
+
+#[tokio::test]
+async fn revisions_restart_after_expiry() {
+    let store = InMemoryProjectionStore::new();
+    assert_eq!(store.compare_and_set("lease", None, b"a", Some(SHORT_TTL)).await.unwrap(), 1);
+    assert_eq!(store.compare_and_set("lease", Some(1), b"b", Some(SHORT_TTL)).await.unwrap(), 2);
+
+    outlive_short_ttl().await;
+    assert!(matches!(
+        store.compare_and_set("lease", Some(2), b"c", None).await,
+        Err(InfrastructureError::Concurrency(_))
+    ));
+    assert!(matches!(store.compare_and_delete("lease", 2).await, Err(InfrastructureError::Concurrency(_))));
+    assert_eq!(store.compare_and_set("lease", None, b"c", None).await.unwrap(), 1);
+    assert_eq!(store.get_versioned("lease").await.unwrap().unwrap().expires_at, None);
+}
+
+#[tokio::test]
+async fn out_of_range_ttl_is_an_invalid_query() {
+    let store = InMemoryProjectionStore::new();
+    assert!(matches!(
+        store.save_with_ttl("k", b"a", Duration::MAX).await,
+        Err(InfrastructureError::InvalidQuery(_))
+    ));
+    assert!(matches!(
+        store.compare_and_set("k", None, b"a", Some(Duration::MAX)).await,
+        Err(InfrastructureError::InvalidQuery(_))
+    ));
+    assert!(store.is_empty().await);
+}
+
+#[tokio::test]
+async fn purge_expired_removes_only_expired_entries() {
+    let store = InMemoryProjectionStore::new();
+    store.save_with_ttl("a", b"1", SHORT_TTL).await.unwrap();
+    store.save_with_ttl("b", b"2", SHORT_TTL).await.unwrap();
+    store.save_with_ttl("c", b"3", Duration::from_secs(3600)).await.unwrap();
+    store.save("d", b"4").await.unwrap();
+    assert_eq!(store.purge_expired().await.unwrap(), 0);
+
+    outlive_short_ttl().await;
+    assert_eq!(store.purge_expired().await.unwrap(), 2);
+    assert_eq!(store.len().await, 2);
+    assert_eq!(store.purge_expired().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn expiry_sweeper_purges_until_stopped() {
+    let store = Arc::new(InMemoryProjectionStore::new());
+    store.save_with_ttl("a", b"1", SHORT_TTL).await.unwrap();
+    store.save("b", b"2").await.unwrap();
+
+    let sweeper = Arc::new(ExpirySweeper::new(store.clone()).with_interval(Duration::from_millis(10)));
+    outlive_short_ttl().await;
+    assert_eq!(sweeper.sweep().await.unwrap(), 1);
+    assert_eq!(store.len().await, 1);
+
+    store.save_with_ttl("c", b"3", SHORT_TTL).await.unwrap();
+    let handle = {
+        let sweeper = sweeper.clone();
+        tokio::spawn(async move { sweeper.run().await })
+    };
+    while store.len().await > 1 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    sweeper.stop();
+    tokio::time::timeout(Duration::from_secs(1), handle).await.unwrap().unwrap();
+    assert_eq!(store.get("b").await.unwrap().as_deref(), Some(b"2".as_slice()));
+}
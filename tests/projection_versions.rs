@@ -0,0 +1,108 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use project_struct_base::application::ports::{CheckpointStore, EventStore, ProjectionStore};
+use project_struct_base::application::projections::{Projection, ProjectionContext, ProjectionRebuilder, ProjectionRunner};
+use project_struct_base::infrastructure::event_store::InMemoryEventStore;
+use project_struct_base::infrastructure::projection_store::InMemoryProjectionStore;
+use project_struct_base::shared::errors::{InfraResult, InfrastructureError};
+use project_struct_base::shared::types::{EventEnvelope, ExpectedVersion};
+
+/// Counts events under `count/total`
+struct Count;
+
+#[async_trait]
+impl Projection for Count {
+    fn name(&self) -> &str {
+        "count"
+    }
+
+    async fn handle(&self, _event: &EventEnvelope<Value>, ctx: &mut ProjectionContext<'_>) -> InfraResult<()> {
+        let total = match ctx.get("count/total").await? {
+            Some(raw) => String::from_utf8(raw).unwrap().parse::<u64>().unwrap(),
+            None => 0,
+        };
+        ctx.save("count/total", (total + 1).to_string().into_bytes());
+        Ok(())
+    }
+}
+
+async fn append_events(events: &InMemoryEventStore, count: usize) {
+    for _ in 0..count {
+        events
+            .append(Uuid::new_v4(), ExpectedVersion::Any, vec![("Counted".into(), json!({}))], Default::default())
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn rebuild_from_v0_then_purge_v0() {
+    let events = Arc::new(InMemoryEventStore::new());
+    let store = Arc::new(InMemoryProjectionStore::new());
+    append_events(&events, 4).await;
+
+    // Version 0: a plain runner writing un-namespaced keys
+    let runner = Arc::new(ProjectionRunner::new(events.clone(), store.clone(), Arc::new(Count)));
+    let handle = {
+        let runner = runner.clone();
+        tokio::spawn(async move { runner.run().await })
+    };
+    while store.get("count/total").await.unwrap().as_deref() != Some(b"4".as_slice()) {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    runner.stop();
+    handle.await.unwrap();
+    store.save("other/key", b"kept").await.unwrap();
+
+    let rebuilder = ProjectionRebuilder::new(events.clone(), store.clone()).with_batch_size(3);
+    let versions = rebuilder.versions();
+    assert!(
+        matches!(versions.purge_unversioned("count", "count").await, Err(InfrastructureError::InvalidQuery(_))),
+        "v0 is still active"
+    );
+
+    append_events(&events, 2).await;
+    let progress = rebuilder.rebuild(&Count, 1, |_| ()).await.unwrap();
+    assert_eq!(progress.position, 6);
+    assert_eq!(versions.active_version("count").await.unwrap(), 1);
+
+    assert_eq!(versions.purge_unversioned("count", "count").await.unwrap(), 1);
+    assert!(store.get("count/total").await.unwrap().is_none());
+    assert_eq!(store.load_checkpoint("count").await.unwrap(), 0);
+    assert_eq!(store.get("other/key").await.unwrap().as_deref(), Some(b"kept".as_slice()));
+
+    // The active version and its namespace survive the purge
+    let reader = versions.reader("count").await.unwrap();
+    assert_eq!(reader.get("count/total").await.unwrap().as_deref(), Some(b"6".as_slice()));
+    assert!(matches!(versions.purge("count", 1).await, Err(InfrastructureError::InvalidQuery(_))));
+}
+
+#[tokio::test]
+async fn purge_refuses_the_active_version_and_can_be_repeated() {
+    let events = Arc::new(InMemoryEventStore::new());
+    let store = Arc::new(InMemoryProjectionStore::new());
+    append_events(&events, 3).await;
+
+    let rebuilder = ProjectionRebuilder::new(events.clone(), store.clone());
+    let versions = rebuilder.versions();
+    rebuilder.rebuild(&Count, 1, |_| ()).await.unwrap();
+    rebuilder.rebuild(&Count, 2, |_| ()).await.unwrap();
+    assert_eq!(versions.active_version("count").await.unwrap(), 2);
+
+    let refused = versions.purge("count", 2).await;
+    assert!(matches!(refused, Err(InfrastructureError::InvalidQuery(_))), "{refused:?}");
+    let reader = versions.reader("count").await.unwrap();
+    assert_eq!(reader.get("count/total").await.unwrap().as_deref(), Some(b"3".as_slice()));
+
+    assert_eq!(versions.purge("count", 1).await.unwrap(), 1);
+    assert!(!versions.purge_pending("count", 1).await.unwrap());
+    // Purging again finds nothing left, as when finishing an interrupted purge
+    assert_eq!(versions.purge("count", 1).await.unwrap(), 0);
+    assert_eq!(versions.store_for("count", 1).load_checkpoint("count").await.unwrap(), 0);
+
+    // The purged version can be rebuilt and activated again
+    rebuilder.rebuild(&Count, 1, |_| ()).await.unwrap();
+    assert_eq!(versions.active_version("count").await.unwrap(), 1);
+}
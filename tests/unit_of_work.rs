@@ -0,0 +1,41 @@
+//! Needs a database: `DATABASE_URL=postgres://... cargo test --test unit_of_work -- --ignored`
+
+use sqlx::PgPool;
+use project_struct_base::infrastructure::repositories::UnitOfWork;
+use project_struct_base::shared::errors::InfrastructureError;
+
+async fn pool() -> PgPool {
+    PgPool::connect(&std::env::var("DATABASE_URL").expect("DATABASE_URL")).await.unwrap()
+}
+
+fn is_misuse<T>(result: Result<T, InfrastructureError>) -> bool {
+    matches!(result, Err(InfrastructureError::InvalidQuery(_)))
+}
+
+#[tokio::test]
+#[ignore = "needs DATABASE_URL"]
+async fn using_a_committed_unit_of_work_fails() {
+    let pool = pool().await;
+    let uow = UnitOfWork::begin(&pool).await.unwrap();
+    {
+        let mut connection = uow.connection().await.unwrap();
+        sqlx::query("SELECT 1").execute(&mut *connection).await.unwrap();
+    }
+    uow.clone().commit().await.unwrap();
+
+    assert!(uow.is_completed().await);
+    assert!(is_misuse(uow.connection().await));
+    assert!(is_misuse(uow.commit().await));
+    assert!(is_misuse(uow.rollback().await));
+}
+
+#[tokio::test]
+#[ignore = "needs DATABASE_URL"]
+async fn using_a_rolled_back_unit_of_work_fails() {
+    let pool = pool().await;
+    let uow = UnitOfWork::begin(&pool).await.unwrap();
+    uow.rollback().await.unwrap();
+
+    assert!(is_misuse(uow.connection().await));
+    assert!(is_misuse(uow.commit().await));
+}